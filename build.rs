@@ -27,7 +27,7 @@ fn add_resources(source_path: &PathBuf, target_path: &PathBuf) -> io::Result<()>
             }
         }
         Err(_) => {
-            fs::copy(&source_path, &target_path)?;
+            fs::copy(source_path, target_path)?;
         }
     }
     Ok(())
@@ -1,10 +1,10 @@
-//! A crate to custom derive `Position` and `Advance` for any type that has a field named `vector`
+//! A crate to custom derive `Position` and `Advance` for any type that has a field named `vector`,
+//! and `Interpolate` for any type that has a field named `previous_position`
 
 extern crate proc_macro;
 
 use crate::proc_macro::TokenStream;
 use quote::quote;
-use syn;
 
 #[proc_macro_derive(Advance)]
 pub fn advance_derive(input: TokenStream) -> TokenStream {
@@ -40,3 +40,17 @@ pub fn position_derive(input: TokenStream) -> TokenStream {
     };
     gen.into()
 }
+
+#[proc_macro_derive(Interpolate)]
+pub fn interpolate_derive(input: TokenStream) -> TokenStream {
+    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+
+    let name = &ast.ident;
+    let gen = quote! {
+        impl Interpolate for #name {
+            fn previous_position(&self) -> Point { self.previous_position }
+            fn previous_position_mut(&mut self) -> &mut Point { &mut self.previous_position }
+        }
+    };
+    gen.into()
+}
@@ -1,10 +1,13 @@
 mod point;
+mod shape;
 mod size;
+pub mod torus;
 mod traits;
 #[macro_use]
 mod vector;
 
 pub use self::vector::Vector;
 pub use self::point::Point;
+pub use self::shape::Shape;
 pub use self::size::Size;
-pub use self::traits::{Advance, Collide, Position};
+pub use self::traits::{Advance, Collide, Interpolate, Position};
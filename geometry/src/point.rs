@@ -1,11 +1,16 @@
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use super::Size;
 
-use std::ops::{Add, Div, Mul, Sub};
+use std::ops::{Add, Div, Mul, Neg, Sub};
 
-/// A `Point` represents a position in space
-#[derive(Clone, Default, Copy)]
+/// A `Point` represents a position in space, or the offset between two positions
+///
+/// Points are 2D vectors: they can be added, scaled, rotated and so on. Angles are measured in
+/// radians, where 0.0 points to the right and a positive angle is a clockwise rotation (the y axis
+/// points down on the screen).
+#[derive(Clone, Default, Copy, Debug, Serialize, Deserialize)]
 pub struct Point {
     pub x: f32,
     pub y: f32,
@@ -14,7 +19,12 @@ pub struct Point {
 impl Point {
     /// Returns a new `Point` with the given coordinates
     pub fn new(x: f32, y: f32) -> Point {
-        Point { x: x, y: y }
+        Point { x, y }
+    }
+
+    /// Returns the point of length 1 in the given direction
+    pub fn from_angle(radians: f32) -> Point {
+        Point::new(radians.cos(), radians.sin())
     }
 
     /// Returns a random `Point` within the given bounds (exclusive)
@@ -25,31 +35,83 @@ impl Point {
         }
     }
 
+    /// Returns the dot product of this point and the given one
+    pub fn dot(self, other: Point) -> f32 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Returns the cross product of this point and the given one (the z coordinate of the 3D cross
+    /// product), which is positive when `other` is clockwise from this point
+    pub fn cross(self, other: Point) -> f32 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Returns the squared distance from the origin to this point
+    pub fn squared_length(self) -> f32 {
+        self.dot(self)
+    }
+
+    /// Returns the distance from the origin to this point
+    pub fn length(self) -> f32 {
+        self.x.hypot(self.y)
+    }
+
+    /// Returns the point in the same direction with a length of 1, or the origin if this point is
+    /// the origin (which has no direction)
+    pub fn normalize(self) -> Point {
+        let length = self.length();
+        if length == 0.0 {
+            self
+        } else {
+            self / length
+        }
+    }
+
+    /// Returns the direction from the origin to this point, between -PI and PI (0.0 for the
+    /// origin)
+    pub fn angle(self) -> f32 {
+        self.y.atan2(self.x)
+    }
+
+    /// Returns the point at `t` of the way from this point to the given one, where 0.0 is this
+    /// point and 1.0 the other one
+    pub fn lerp(self, other: Point, t: f32) -> Point {
+        self + (other - self) * t
+    }
+
     /// Returns the squared distance from this point to the given one
     pub fn squared_distance_to(self, target: Point) -> f32 {
-        (self.x - target.x) * (self.x - target.x) + (self.y - target.y) * (self.y - target.y)
+        (target - self).squared_length()
+    }
+
+    /// Returns the squared distance from this point to the segment from `start` to `end`
+    pub fn squared_distance_to_segment(self, start: Point, end: Point) -> f32 {
+        let segment = end - start;
+        let length = segment.squared_length();
+        let t = if length == 0.0 { 0.0 } else { ((self - start).dot(segment) / length).clamp(0.0, 1.0) };
+        self.squared_distance_to(start.lerp(end, t))
     }
 
     /// Rotates the point through the origin in the given angle (radians)
-    pub fn rotate(mut self, radians: f32) -> Point {
-        let radius = (self.x * self.x + self.y * self.y).sqrt();
-        let point_angle = (self.y / self.x).atan();
-        let final_angle = point_angle + radians;
-        self.x = final_angle.cos() * radius;
-        self.y = final_angle.sin() * radius;
-        self
+    pub fn rotate(self, radians: f32) -> Point {
+        let (sin, cos) = radians.sin_cos();
+        Point::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
     }
 
     /// Translates the point by another point
-    pub fn translate(mut self, other: &Point) -> Point {
-        self.x += other.x;
-        self.y += other.y;
-        self
+    pub fn translate(self, other: &Point) -> Point {
+        self + *other
     }
 
     /// Checks if this point is contained in a circle
     pub fn intersect_circle(self, center: &Point, radius: f32) -> bool {
-        (self.x - center.x).powi(2) + (self.y - center.y).powi(2) < radius.powi(2)
+        self.squared_distance_to(*center) < radius * radius
+    }
+
+    /// Checks if the segment from this point to `end` passes through a circle, which is how a
+    /// moving point is tested against a circle along its whole path
+    pub fn segment_intersects_circle(self, end: Point, center: &Point, radius: f32) -> bool {
+        center.squared_distance_to_segment(self, end) < radius * radius
     }
 }
 
@@ -108,6 +170,18 @@ impl Sub<f32> for Point {
     }
 }
 
+/// Implements the unary '-' operator for Point, which points the other way
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
 /// Implements the '*' operator for Point * Point
 impl Mul for Point {
     type Output = Point;
@@ -127,7 +201,7 @@ impl Mul<f32> for Point {
     fn mul(self, _rhs: f32) -> Point {
         Point {
             x: self.x * _rhs,
-            y: self.x * _rhs,
+            y: self.y * _rhs,
         }
     }
 }
@@ -0,0 +1,184 @@
+//! Shapes of objects, for collision detection that is more precise than circles
+//!
+//! A `Shape` is described relative to the position of its object. Every shape is made of convex
+//! pieces, and two convex pieces are tested with the separating axis theorem: they don't
+//! intersect if and only if there is an axis on which their projections don't overlap. For two
+//! polygons it suffices to try the normals of their edges.
+//!
+//! Circles and capsules are handled as a point or a segment grown by a radius, so they intersect
+//! another piece when they are closer to it than that radius.
+
+use std::borrow::Cow;
+
+use super::Point;
+
+/// The shape of an object, relative to its position
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    /// A circle with the given center and radius
+    Circle { center: Point, radius: f32 },
+    /// A convex polygon, with its vertices in order (in either direction)
+    Polygon(Vec<Point>),
+    /// The points within `radius` of the segment from `start` to `end`
+    Capsule { start: Point, end: Point, radius: f32 },
+    /// Several shapes that together make up the shape of an object
+    Compound(Vec<Shape>),
+}
+
+impl Shape {
+    /// Returns a circle with the given radius, centered at the position of the object
+    pub fn circle(radius: f32) -> Shape {
+        Shape::Circle { center: Point::default(), radius }
+    }
+
+    /// Returns a convex polygon with the given vertices, in the format of the polygons used to
+    /// draw the objects
+    pub fn polygon(vertices: &[[f32; 2]]) -> Shape {
+        Shape::Polygon(vertices.iter().map(|&[x, y]| Point::new(x, y)).collect())
+    }
+
+    /// Returns the shape rotated around the position of the object in the given angle (radians)
+    pub fn rotate(&self, radians: f32) -> Shape {
+        self.map(&|point| point.rotate(radians))
+    }
+
+    /// Returns the shape moved by the given offset
+    pub fn translate(&self, offset: Point) -> Shape {
+        self.map(&|point| point + offset)
+    }
+
+    /// Returns the radius of the smallest circle around the position of the object that contains
+    /// the whole shape
+    pub fn bounding_radius(&self) -> f32 {
+        match self {
+            Shape::Circle { center, radius } => center.length() + radius,
+            Shape::Polygon(vertices) => vertices.iter().map(|v| v.length()).fold(0.0, f32::max),
+            Shape::Capsule { start, end, radius } => start.length().max(end.length()) + radius,
+            Shape::Compound(shapes) => shapes.iter().map(Shape::bounding_radius).fold(0.0, f32::max),
+        }
+    }
+
+    /// Returns true if the two shapes intersect, given that they are relative to the same position
+    ///
+    /// Shapes that only touch each other don't intersect
+    pub fn intersects(&self, other: &Shape) -> bool {
+        match (self, other) {
+            (Shape::Compound(shapes), _) => shapes.iter().any(|shape| shape.intersects(other)),
+            (_, Shape::Compound(shapes)) => shapes.iter().any(|shape| self.intersects(shape)),
+            (Shape::Circle { center: a, radius: r }, Shape::Circle { center: b, radius: s }) => {
+                a.squared_distance_to(*b) < (r + s) * (r + s)
+            }
+            _ => {
+                let ((a, r), (b, s)) = (self.core(), other.core());
+                overlap(&a, &b) || squared_distance(&a, &b) < (r + s) * (r + s)
+            }
+        }
+    }
+
+    // Returns the shape with `f` applied to all of its points
+    fn map(&self, f: &dyn Fn(Point) -> Point) -> Shape {
+        match self {
+            Shape::Circle { center, radius } => Shape::Circle { center: f(*center), radius: *radius },
+            Shape::Polygon(vertices) => Shape::Polygon(vertices.iter().map(|&v| f(v)).collect()),
+            Shape::Capsule { start, end, radius } => Shape::Capsule { start: f(*start), end: f(*end), radius: *radius },
+            Shape::Compound(shapes) => Shape::Compound(shapes.iter().map(|shape| shape.map(f)).collect()),
+        }
+    }
+
+    // Returns the convex polygon (or point, or segment) at the core of a shape that isn't compound,
+    // and the radius it is grown by
+    fn core(&self) -> (Cow<'_, [Point]>, f32) {
+        match self {
+            Shape::Circle { center, radius } => (Cow::Borrowed(std::slice::from_ref(center)), *radius),
+            Shape::Polygon(vertices) => (Cow::Borrowed(vertices), 0.0),
+            Shape::Capsule { start, end, radius } => (Cow::Owned(vec![*start, *end]), *radius),
+            Shape::Compound(_) => unreachable!("compound shapes are split before getting their core"),
+        }
+    }
+}
+
+// Returns true if two cores overlap, which can only be when one of them is a polygon (points and
+// segments have no area, so they can only be closer than their radii)
+fn overlap(a: &[Point], b: &[Point]) -> bool {
+    (a.len() >= 3 || b.len() >= 3) && !separated(a, b) && !separated(b, a)
+}
+
+// Returns true if one of the edge normals of `a` separates the projections of `a` and `b`
+fn separated(a: &[Point], b: &[Point]) -> bool {
+    edges(a).any(|(start, end)| {
+        let axis = Point::new(start.y - end.y, end.x - start.x);
+        let (a_min, a_max) = project(a, axis);
+        let (b_min, b_max) = project(b, axis);
+        axis != Point::default() && (a_max <= b_min || b_max <= a_min)
+    })
+}
+
+// Returns the interval covered by the projections of the points on `axis`
+fn project(points: &[Point], axis: Point) -> (f32, f32) {
+    points.iter().map(|p| p.dot(axis)).fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), k| (min.min(k), max.max(k)))
+}
+
+// Returns the edges of a polygon, a segment (both ways) or a point (from itself to itself)
+fn edges(points: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    points.iter().cloned().zip(points.iter().cloned().cycle().skip(1))
+}
+
+// Returns the squared distance between the outlines of two cores
+fn squared_distance(a: &[Point], b: &[Point]) -> f32 {
+    edges(a)
+        .flat_map(|(a1, a2)| edges(b).map(move |(b1, b2)| segments_squared_distance(a1, a2, b1, b2)))
+        .fold(f32::INFINITY, f32::min)
+}
+
+// Returns the squared distance between the segments from `a1` to `a2` and from `b1` to `b2`
+fn segments_squared_distance(a1: Point, a2: Point, b1: Point, b2: Point) -> f32 {
+    let (a, b) = (a2 - a1, b2 - b1);
+    let crossing = a.cross(b1 - a1) * a.cross(b2 - a1) < 0.0 && b.cross(a1 - b1) * b.cross(a2 - b1) < 0.0;
+    if crossing {
+        0.0
+    } else {
+        // Segments that don't cross are closest at one of their ends
+        a1.squared_distance_to_segment(b1, b2)
+            .min(a2.squared_distance_to_segment(b1, b2))
+            .min(b1.squared_distance_to_segment(a1, a2))
+            .min(b2.squared_distance_to_segment(a1, a2))
+    }
+}
+
+#[test]
+fn test_shape() {
+    let triangle = Shape::polygon(&[[-10.0, -8.0], [10.0, 0.0], [-10.0, 8.0]]);
+    let circle = |x, y, radius| Shape::circle(radius).translate(Point::new(x, y));
+
+    // A circle next to the tip of the triangle is within its bounding circle, but doesn't touch it
+    assert!(triangle.bounding_radius() > 12.8 && triangle.bounding_radius() < 12.9);
+    assert!(!triangle.intersects(&circle(8.0, 6.0, 2.0)));
+    assert!(triangle.intersects(&circle(12.0, 0.0, 2.5)));
+    assert!(triangle.intersects(&circle(0.0, 0.0, 1.0)));
+    assert!(circle(0.0, 0.0, 1.0).intersects(&circle(1.5, 0.0, 1.0)));
+    assert!(!circle(0.0, 0.0, 1.0).intersects(&circle(2.0, 0.0, 1.0)));
+
+    // Rotated upside down, the tip points to the left
+    let rotated = triangle.rotate(std::f32::consts::PI);
+    assert!(rotated.intersects(&circle(8.0, 6.0, 2.0)));
+    assert!(!rotated.intersects(&circle(-8.0, 6.0, 2.0)));
+
+    // Polygons are separated by the normals of their edges
+    let square = Shape::polygon(&[[0.0, 0.0], [4.0, 0.0], [4.0, 4.0], [0.0, 4.0]]);
+    assert!(!triangle.intersects(&square.translate(Point::new(4.0, 4.0))));
+    assert!(triangle.intersects(&square.translate(Point::new(2.0, 2.0))));
+    assert!(triangle.intersects(&square.translate(Point::new(-2.0, -2.0))));
+
+    // A capsule crossing the triangle, without any of its ends being close to it
+    let capsule = Shape::Capsule { start: Point::new(0.0, -20.0), end: Point::new(0.0, 20.0), radius: 1.0 };
+    assert!(capsule.intersects(&triangle));
+    assert!(!capsule.translate(Point::new(13.0, 0.0)).intersects(&triangle));
+    assert!(capsule.intersects(&circle(1.5, 15.0, 1.0)));
+
+    // A compound shape intersects what any of its pieces intersects
+    let compound = Shape::Compound(vec![circle(-30.0, 0.0, 5.0), circle(30.0, 0.0, 5.0)]);
+    assert_eq!(compound.bounding_radius(), 35.0);
+    assert!(compound.intersects(&circle(34.0, 0.0, 1.0)));
+    assert!(!compound.intersects(&triangle));
+    assert!(triangle.intersects(&compound.translate(Point::new(38.0, 0.0))));
+}
@@ -1,7 +1,8 @@
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 /// A `Size` represents a region in space
-#[derive(Clone, Copy, Default)]
+#[derive(Clone, Copy, Default, Debug, Serialize, Deserialize)]
 pub struct Size {
     pub width: f32,
     pub height: f32,
@@ -11,8 +12,8 @@ impl Size {
     /// Returns a new `Size` of the given dimensions
     pub fn new(width: f32, height: f32) -> Size {
         Size {
-            width: width,
-            height: height,
+            width,
+            height,
         }
     }
 
@@ -0,0 +1,107 @@
+//! Geometry of a world that wraps around its edges
+//!
+//! Objects that leave the world on one side come back on the other side, which makes the world a
+//! torus: every point has copies one world away in each direction, and the distance between two
+//! points is the distance to the nearest copy. The functions below answer questions about points
+//! in such a world, given its `bounds`.
+
+use super::{Point, Size};
+
+/// Returns the shortest offset that takes `from` to `to` (or to one of its copies)
+pub fn displacement(from: Point, to: Point, bounds: Size) -> Point {
+    Point::new(shortest(to.x - from.x, bounds.width), shortest(to.y - from.y, bounds.height))
+}
+
+/// Returns the copy of `to` that is nearest to `from`, which might be outside of the bounds
+pub fn nearest_copy(from: Point, to: Point, bounds: Size) -> Point {
+    from + displacement(from, to, bounds)
+}
+
+/// Returns the squared distance between two points, measured the shortest way around
+pub fn squared_distance(a: Point, b: Point, bounds: Size) -> f32 {
+    displacement(a, b, bounds).squared_length()
+}
+
+/// Returns the distance between two points, measured the shortest way around
+pub fn distance(a: Point, b: Point, bounds: Size) -> f32 {
+    squared_distance(a, b, bounds).sqrt()
+}
+
+/// Returns the direction (in radians, see `Advance::direction`) in which `from` has to head to
+/// reach `to` the shortest way
+pub fn angle_to(from: Point, to: Point, bounds: Size) -> f32 {
+    displacement(from, to, bounds).angle()
+}
+
+/// Checks if `point` is contained in a circle, which might reach across the edges
+pub fn intersect_circle(point: Point, center: Point, radius: f32, bounds: Size) -> bool {
+    squared_distance(point, center, bounds) < radius * radius
+}
+
+/// Checks if the segment from `start` to `end` passes through a circle, where the segment is the
+/// shortest way between them and both the segment and the circle might reach across the edges
+///
+/// This is how an object that moved from `start` to `end` is tested against a circle along its
+/// whole path, even if it wrapped around on the way
+pub fn intersect_segment_circle(start: Point, end: Point, center: Point, radius: f32, bounds: Size) -> bool {
+    // Measure from the middle of the segment, so the nearest copy of the circle is the one that
+    // the segment can reach
+    let end = start + displacement(start, end, bounds);
+    let center = nearest_copy(start.lerp(end, 0.5), center, bounds);
+    start.segment_intersects_circle(end, &center, radius)
+}
+
+/// Returns the copy of `point` that lies within the bounds
+pub fn normalize(point: Point, bounds: Size) -> Point {
+    Point::new(wrap(point.x, bounds.width), wrap(point.y, bounds.height))
+}
+
+// Returns the shortest offset along an axis that wraps around at `bound`, between -bound / 2 and
+// bound / 2
+fn shortest(delta: f32, bound: f32) -> f32 {
+    let delta = delta % bound;
+    if delta > bound / 2.0 {
+        delta - bound
+    } else if delta < -bound / 2.0 {
+        delta + bound
+    } else {
+        delta
+    }
+}
+
+// Returns the coordinate wrapped between 0 (inclusive) and `bound` (exclusive)
+fn wrap(k: f32, bound: f32) -> f32 {
+    let k = k.rem_euclid(bound);
+    // Rounding can make tiny negative numbers end up exactly at the bound
+    if k >= bound { 0.0 } else { k }
+}
+
+#[test]
+fn test_torus() {
+    let bounds = Size::new(1000.0, 500.0);
+    let left = Point::new(10.0, 250.0);
+    let right = Point::new(980.0, 250.0);
+
+    // The shortest way between the two points crosses the left edge
+    assert_eq!(displacement(left, right, bounds), Point::new(-30.0, 0.0));
+    assert_eq!(nearest_copy(left, right, bounds), Point::new(-20.0, 250.0));
+    assert_eq!(distance(left, right, bounds), 30.0);
+    assert_eq!(angle_to(left, right, bounds), std::f32::consts::PI);
+    assert_eq!(angle_to(right, left, bounds), 0.0);
+    assert!(intersect_circle(left, right, 31.0, bounds));
+    assert!(!intersect_circle(left, Point::new(500.0, 250.0), 31.0, bounds));
+
+    // A segment that passes by a circle on its way from one point to the other, the short way
+    let circle = Point::new(995.0, 240.0);
+    assert!(intersect_segment_circle(right, left, circle, 11.0, bounds));
+    assert!(intersect_segment_circle(left, right, circle, 11.0, bounds));
+    assert!(!intersect_segment_circle(right, left, circle, 9.0, bounds));
+    assert!(!intersect_segment_circle(right, left, Point::new(500.0, 250.0), 20.0, bounds));
+    assert!(intersect_segment_circle(right, left, Point::new(-5.0, 255.0), 6.0, bounds));
+
+    // Also across a corner, and for points that are far outside of the bounds
+    assert_eq!(distance(Point::new(1.0, 1.0), Point::new(998.0, 497.0), bounds), 5.0);
+    assert_eq!(distance(Point::new(1.0, 1.0), Point::new(-3003.0, 1498.0), bounds), 5.0);
+    assert_eq!(normalize(Point::new(-20.0, 1250.0), bounds), Point::new(980.0, 250.0));
+    assert_eq!(normalize(Point::new(1000.0, 0.0), bounds), Point::new(0.0, 0.0));
+}
@@ -2,7 +2,7 @@
 
 use std::f32;
 
-use super::{Point, Size};
+use super::{torus, Point, Shape, Size};
 
 /// A trait for objects that occupy a position in space
 pub trait Position {
@@ -36,20 +36,26 @@ pub trait Advance: Position {
     fn direction_mut(&mut self) -> &mut f32;
 
     /// Changes the direction of the vector to point to the given target
+    ///
+    /// The direction doesn't change if the target is at the object's position
     fn point_to(&mut self, target: Point) {
-        let m = (self.y() - target.y) / (self.x() - target.x);
+        let offset = target - self.position();
+        if offset != Point::default() {
+            *self.direction_mut() = offset.angle();
+        }
+    }
 
-        *self.direction_mut() = if target.x > self.x() {
-            m.atan()
-        } else {
-            m.atan() + f32::consts::PI
-        };
+    /// Similar to `Advance::point_to`, but the target is reached through the shortest way around
+    /// the given bounds (objects that leave the bounds on one side come back on the other side)
+    fn point_to_wrapping(&mut self, target: Point, bounds: Size) {
+        self.point_to(torus::nearest_copy(self.position(), target, bounds));
     }
 
     /// Advances the object in the given amount of units, according to its direction
     fn advance(&mut self, units: f32) {
-        *self.x_mut() += self.direction().cos() * units;
-        *self.y_mut() += self.direction().sin() * units;
+        let position = self.position() + Point::from_angle(self.direction()) * units;
+        *self.x_mut() = position.x;
+        *self.y_mut() = position.y;
     }
 
     /// Similar to `Advance::advance`, but the final position will be wrapped
@@ -57,24 +63,55 @@ pub trait Advance: Position {
     fn advance_wrapping(&mut self, units: f32, bounds: Size) {
         self.advance(units);
 
-        fn wrap(k: &mut f32, bound: f32) {
-            if *k < 0.0 {
-                *k += bound;
-            } else if *k >= bound {
-                *k -= bound;
-            }
+        let position = torus::normalize(self.position(), bounds);
+        *self.x_mut() = position.x;
+        *self.y_mut() = position.y;
+    }
+}
+
+/// A trait for moving objects that remember their position at the previous simulation step, so
+/// they can be drawn smoothly in between steps
+pub trait Interpolate: Position {
+    /// Returns the position of the object before the last step
+    fn previous_position(&self) -> Point;
+
+    /// Returns a mutable reference to the position of the object before the last step
+    fn previous_position_mut(&mut self) -> &mut Point;
+
+    /// Remembers the current position, should be called right before each step
+    fn store_position(&mut self) {
+        *self.previous_position_mut() = self.position();
+    }
+
+    /// Returns the position between the previous and the current one, where `alpha` goes from 0.0
+    /// (previous position) to 1.0 (current position)
+    fn interpolated_position(&self, alpha: f32, bounds: Size) -> Point {
+        let previous = self.previous_position();
+        let current = self.position();
+
+        // If the object wrapped around the edge of the screen we don't interpolate, otherwise it
+        // would be drawn somewhere in the middle of the screen
+        if (current.x - previous.x).abs() > bounds.width / 2.0
+            || (current.y - previous.y).abs() > bounds.height / 2.0
+        {
+            return current;
         }
 
-        wrap(self.x_mut(), bounds.width);
-        wrap(self.y_mut(), bounds.height);
+        Point::new(
+            previous.x + (current.x - previous.x) * alpha,
+            previous.y + (current.y - previous.y) * alpha,
+        )
     }
 }
 
-/// A trait that provides collision detection for objects with a position and a radius
+/// A trait that provides collision detection for objects with a position and a shape
 ///
-/// For collision purposes, all objects are treated as circles
+/// Most objects are treated as circles, but objects can provide a more precise `Shape`
 pub trait Collide: Position {
     /// Returns the radius of the object
+    ///
+    /// For objects with a shape other than a circle, this is the radius of a circle around their
+    /// position that contains their whole shape
     fn radius(&self) -> f32;
 
     /// Returns the diameter of the objects
@@ -82,9 +119,53 @@ pub trait Collide: Position {
         self.radius() * 2.0
     }
 
+    /// Returns the shape of the object, relative to its position and rotated according to its
+    /// direction (if it has one)
+    ///
+    /// By default this is a circle with the radius of the object
+    fn shape(&self) -> Shape {
+        Shape::circle(self.radius())
+    }
+
     /// Returns true if the two objects collide and false otherwise
     fn collides_with<O: Collide>(&self, other: &O) -> bool {
-        let radii = self.radius() + other.radius();
-        self.position().squared_distance_to(other.position()) < radii * radii
+        shapes_collide(self, other, other.position())
+    }
+
+    /// Similar to `Collide::collides_with`, but objects also collide across the edges of the
+    /// given bounds (since objects that leave the bounds on one side come back on the other side)
+    fn collides_with_wrapping<O: Collide>(&self, other: &O, bounds: Size) -> bool {
+        shapes_collide(self, other, torus::nearest_copy(self.position(), other.position(), bounds))
     }
 }
+
+// Returns true if the shapes of the two objects intersect, when `other` is at the given position
+fn shapes_collide<A: Collide + ?Sized, B: Collide>(object: &A, other: &B, other_position: Point) -> bool {
+    // Objects whose radii don't reach each other can't collide, which spares most shape tests
+    let offset = other_position - object.position();
+    let radii = object.radius() + other.radius();
+    offset.squared_length() < radii * radii && object.shape().intersects(&other.shape().translate(offset))
+}
+
+#[test]
+fn test_collides_with_wrapping() {
+    struct Circle(Point);
+    impl Position for Circle {
+        fn x(&self) -> f32 { self.0.x }
+        fn y(&self) -> f32 { self.0.y }
+        fn x_mut(&mut self) -> &mut f32 { &mut self.0.x }
+        fn y_mut(&mut self) -> &mut f32 { &mut self.0.y }
+    }
+    impl Collide for Circle {
+        fn radius(&self) -> f32 { 10.0 }
+    }
+
+    // Two circles touching across the left and right edge, and across a corner
+    let bounds = Size::new(1000.0, 500.0);
+    let (left, right) = (Circle(Point::new(5.0, 250.0)), Circle(Point::new(990.0, 250.0)));
+    assert!(!left.collides_with(&right));
+    assert!(left.collides_with_wrapping(&right, bounds));
+    let (top_left, bottom_right) = (Circle(Point::new(2.0, 2.0)), Circle(Point::new(998.0, 498.0)));
+    assert!(top_left.collides_with_wrapping(&bottom_right, bounds));
+    assert!(!left.collides_with_wrapping(&Circle(Point::new(500.0, 250.0)), bounds));
+}
@@ -1,10 +1,11 @@
 use std::f32;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-use super::{Point, Size};
+use super::{Advance, Point, Position, Size};
 
 /// A `Vector`
-#[derive(Clone, Default)]
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Vector {
     /// The position of the vector
     pub position: Point,
@@ -16,8 +17,8 @@ impl Vector {
     /// Returns a new `Vector`
     pub fn new(position: Point, direction: f32) -> Vector {
         Vector {
-            position: position,
-            direction: direction,
+            position,
+            direction,
         }
     }
 
@@ -29,9 +30,38 @@ impl Vector {
         )
     }
 
+    /// Returns the point of length 1 in the direction of the vector
+    pub fn heading(&self) -> Point {
+        Point::from_angle(self.direction)
+    }
+
     /// Consumes the vector and returns a new one with inverted direction
     pub fn invert(mut self) -> Vector {
         self.direction -= f32::consts::PI;
         self
     }
 }
+
+impl Position for Vector {
+    fn x(&self) -> f32 {
+        self.position.x
+    }
+    fn x_mut(&mut self) -> &mut f32 {
+        &mut self.position.x
+    }
+    fn y(&self) -> f32 {
+        self.position.y
+    }
+    fn y_mut(&mut self) -> &mut f32 {
+        &mut self.position.y
+    }
+}
+
+impl Advance for Vector {
+    fn direction(&self) -> f32 {
+        self.direction
+    }
+    fn direction_mut(&mut self) -> &mut f32 {
+        &mut self.direction
+    }
+}
@@ -0,0 +1,153 @@
+//! Property-based tests of the algebra of points, and of the geometry built on top of them
+
+use std::f32::consts::PI;
+
+use geometry::{torus, Advance, Point, Shape, Size, Vector};
+use proptest::prelude::*;
+
+// Coordinates within a few screens of the origin, where f32 keeps plenty of precision
+fn coordinate() -> impl Strategy<Value = f32> {
+    -5000.0f32..5000.0
+}
+
+fn point() -> impl Strategy<Value = Point> {
+    (coordinate(), coordinate()).prop_map(|(x, y)| Point::new(x, y))
+}
+
+// Circles, capsules and (convex) regular polygons around the origin
+fn shape() -> impl Strategy<Value = Shape> {
+    prop_oneof![
+        (0.1f32..50.0).prop_map(Shape::circle),
+        (point(), 0.1f32..50.0).prop_map(|(end, radius)| Shape::Capsule { start: Point::default(), end: end / 100.0, radius }),
+        (3usize..8, 1.0f32..50.0).prop_map(|(sides, radius)| {
+            Shape::Polygon((0..sides).map(|i| Point::from_angle(i as f32 * 2.0 * PI / sides as f32) * radius).collect())
+        }),
+    ]
+}
+
+fn angle() -> impl Strategy<Value = f32> {
+    -4.0 * PI..4.0 * PI
+}
+
+// Whether two numbers are equal, allowing for rounding errors relative to `scale`
+fn close(a: f32, b: f32, scale: f32) -> bool {
+    (a - b).abs() <= 1e-4 * scale.max(1.0)
+}
+
+fn close_points(a: Point, b: Point, scale: f32) -> bool {
+    close(a.x, b.x, scale) && close(a.y, b.y, scale)
+}
+
+proptest! {
+    #[test]
+    fn addition_is_commutative_and_subtraction_undoes_it(a in point(), b in point()) {
+        prop_assert_eq!(a + b, b + a);
+        prop_assert!(close_points(a + b - b, a, a.length() + b.length()));
+        prop_assert_eq!(a - a, Point::default());
+        prop_assert_eq!(-(-a), a);
+    }
+
+    #[test]
+    fn scaling_scales_both_coordinates(a in point(), k in -10.0f32..10.0) {
+        let scaled = a * k;
+        prop_assert_eq!(scaled, Point::new(a.x * k, a.y * k));
+        prop_assert!(close(scaled.length(), a.length() * k.abs(), a.length() * 10.0));
+    }
+
+    #[test]
+    fn dot_and_cross_products(a in point(), b in point(), k in -10.0f32..10.0) {
+        let scale = a.length() * b.length() * 10.0;
+        prop_assert_eq!(a.dot(b), b.dot(a));
+        prop_assert_eq!(a.cross(b), -b.cross(a));
+        prop_assert!(close(a.dot(b * k), a.dot(b) * k, scale));
+        prop_assert!(close(a.dot(a), a.length() * a.length(), a.squared_length()));
+        prop_assert_eq!(a.cross(a), 0.0);
+    }
+
+    #[test]
+    fn rotation_keeps_lengths_and_adds_angles(a in point(), r in angle(), s in angle()) {
+        let scale = a.length();
+        prop_assert!(close(a.rotate(r).length(), a.length(), scale));
+        prop_assert!(close_points(a.rotate(r).rotate(s), a.rotate(r + s), scale));
+        prop_assert!(close_points(a.rotate(r).rotate(-r), a, scale));
+        // The dot product only depends on the angle between the points
+        let b = a.rotate(1.0);
+        prop_assert!(close(a.rotate(r).dot(b.rotate(r)), a.dot(b), scale * scale));
+    }
+
+    #[test]
+    fn angles_round_trip(r in -PI + 1e-3..PI - 1e-3, length in 0.1f32..1000.0) {
+        let a = Point::from_angle(r) * length;
+        prop_assert!(close(a.angle(), r, 1.0));
+        prop_assert!(close(Point::from_angle(r).length(), 1.0, 1.0));
+        prop_assert!(close_points(Point::new(length, 0.0).rotate(r), a, length));
+    }
+
+    #[test]
+    fn normalizing_keeps_the_direction(a in point()) {
+        prop_assume!(a.length() > 1e-3);
+        let unit = a.normalize();
+        prop_assert!(close(unit.length(), 1.0, 1.0));
+        prop_assert!(close(unit.angle(), a.angle(), 1.0) || close(unit.angle().abs(), PI, 1.0));
+    }
+
+    #[test]
+    fn lerp_goes_from_one_point_to_the_other(a in point(), b in point(), t in 0.0f32..1.0) {
+        let scale = a.length() + b.length();
+        prop_assert!(close_points(a.lerp(b, 0.0), a, scale));
+        prop_assert!(close_points(a.lerp(b, 1.0), b, scale));
+        let between = a.lerp(b, t);
+        prop_assert!(close(a.squared_distance_to(between).sqrt() + between.squared_distance_to(b).sqrt(),
+                           a.squared_distance_to(b).sqrt(), scale));
+    }
+
+    #[test]
+    fn point_to_then_advance_reaches_the_target(from in point(), to in point()) {
+        prop_assume!(from.squared_distance_to(to) > 1.0);
+        let mut vector = Vector::new(from, 0.0);
+        vector.point_to(to);
+        vector.advance(from.squared_distance_to(to).sqrt());
+        prop_assert!(close_points(vector.position, to, from.length() + to.length()));
+    }
+
+    #[test]
+    fn wrapped_distances_are_symmetric_and_short(a in point(), b in point(), width in 100.0f32..2000.0, height in 100.0f32..2000.0) {
+        let bounds = Size::new(width, height);
+        let scale = width + height;
+        prop_assert!(close(torus::distance(a, b, bounds), torus::distance(b, a, bounds), scale));
+        prop_assert!(torus::distance(a, b, bounds) <= (width * width + height * height).sqrt() / 2.0 + 1e-2);
+        let offset = torus::displacement(a, b, bounds);
+        prop_assert!(close_points(torus::normalize(a + offset, bounds), torus::normalize(b, bounds), scale)
+            // Points on the edge might come out on either side of the world
+            || close(torus::distance(a + offset, b, bounds), 0.0, scale));
+        let normalized = torus::normalize(a, bounds);
+        prop_assert!(normalized.x >= 0.0 && normalized.x < width && normalized.y >= 0.0 && normalized.y < height);
+    }
+
+    #[test]
+    fn shapes_intersect_symmetrically(a in shape(), b in shape(), offset in point(), r in angle()) {
+        let b = b.rotate(r).translate(offset / 20.0);
+        prop_assert_eq!(a.intersects(&b), b.intersects(&a));
+        if a.intersects(&b) {
+            prop_assert!((offset / 20.0).length() < a.bounding_radius() + b.bounding_radius());
+        }
+        prop_assert!(a.intersects(&a.rotate(r)));
+    }
+
+    #[test]
+    fn segments_pass_through_the_circles_along_them(start in point(), end in point(), t in 0.0f32..1.0, offset in point()) {
+        let bounds = Size::new(1000.0, 500.0);
+        let (start, end) = (torus::normalize(start, bounds), torus::normalize(end, bounds));
+        let along = torus::nearest_copy(start, end, bounds);
+        let center = start.lerp(along, t) + offset / 2000.0;
+        prop_assert!(start.segment_intersects_circle(along, &center, 6.0));
+        prop_assert!(torus::intersect_segment_circle(start, end, center, 6.0, bounds));
+
+        // Moving everything a whole world away doesn't change anything
+        let shift = Point::new(bounds.width, -bounds.height);
+        prop_assert!(torus::intersect_segment_circle(start + shift, end, center - shift, 6.0, bounds));
+        let missed = center + Point::new(0.0, 250.0);
+        prop_assert_eq!(torus::intersect_segment_circle(start, end, missed, 6.0, bounds),
+                        torus::intersect_segment_circle(start + shift, end - shift, missed + shift, 6.0, bounds));
+    }
+}
@@ -0,0 +1,89 @@
+//! Benchmarks of the collision detection with thousands of objects in the world
+//!
+//! Run them with `cargo bench -p rocket-core`. The `brute_force` benchmarks check every bullet
+//! against every enemy, like the game used to do, to compare them with the `SpatialHash`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng};
+
+use geometry::{Collide, Position, Size, Vector};
+use rocket_core::{
+    models::{Bullet, Enemy, EnemyKind},
+    spatial_hash::SpatialHash,
+    CollisionsController, GameRng, GameState, TimeController,
+};
+
+const SIZE: Size = Size { width: 1920.0, height: 1080.0 };
+const CELL_SIZE: f32 = 64.0;
+
+// Returns the given amount of enemies and bullets, spread randomly over the world
+fn objects(enemies: usize, bullets: usize) -> (Vec<Enemy>, Vec<Bullet>) {
+    let mut rng = GameRng::seed_from_u64(0);
+    let enemies = (0..enemies)
+        .map(|_| Enemy::new(EnemyKind::Chaser, Vector::random(&mut rng, SIZE)))
+        .collect();
+    let bullets = (0..bullets)
+        .map(|_| Bullet::new(Vector::random(&mut rng, SIZE), rng.gen_range(10.0..400.0)))
+        .collect();
+    (enemies, bullets)
+}
+
+fn bullet_enemy(c: &mut Criterion) {
+    let mut group = c.benchmark_group("bullet_enemy");
+    for &amount in &[500, 2000, 5000] {
+        let (enemies, bullets) = objects(amount, amount);
+
+        group.bench_with_input(BenchmarkId::new("brute_force", amount), &amount, |b, _| {
+            b.iter(|| {
+                bullets
+                    .iter()
+                    .filter(|bullet| enemies.iter().any(|enemy| enemy.collides_with_wrapping(*bullet, SIZE)))
+                    .count()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("spatial_hash", amount), &amount, |b, _| {
+            b.iter(|| {
+                // Building the grid is part of the work, since it happens on every update
+                let hash = SpatialHash::from_objects(SIZE, CELL_SIZE, enemies.iter().map(|e| (e.position(), e.radius())));
+                bullets
+                    .iter()
+                    .filter(|bullet| {
+                        let mut hit = false;
+                        hash.query(bullet.position(), bullet.radius(), |i| hit |= enemies[i].collides_with_wrapping(*bullet, SIZE));
+                        hit
+                    })
+                    .count()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn handle_collisions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("handle_collisions");
+    for &amount in &[500, 2000, 5000] {
+        group.bench_with_input(BenchmarkId::from_parameter(amount), &amount, |b, &amount| {
+            let mut rng = GameRng::seed_from_u64(0);
+            let mut state = GameState::new(SIZE, &mut rng);
+            state.reset(&mut rng);
+            let mut time_controller = TimeController::new(&state.config);
+            let mut events = Vec::new();
+
+            b.iter_batched(
+                || objects(amount, amount),
+                |(enemies, bullets)| {
+                    state.world.enemies = enemies;
+                    state.world.bullets = bullets;
+                    CollisionsController::handle_collisions(&mut state, &mut time_controller, &mut events);
+                    events.clear();
+                },
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bullet_enemy, handle_collisions);
+criterion_main!(benches);
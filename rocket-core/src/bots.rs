@@ -0,0 +1,125 @@
+//! Bots that play the game on their own, used to run simulations without a player
+
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    controllers::Actions,
+    game_state::GameState,
+    geometry::{torus, Advance, Position},
+    util,
+    GameRng,
+};
+
+/// The names of the available bots, as accepted by `bot_by_name`
+pub const BOT_NAMES: &[&str] = &["idle", "spinner", "random", "hunter"];
+
+/// A `Policy` decides which actions to take, based on the state of the game
+pub trait Policy {
+    /// Returns the actions to take during the next update
+    fn actions(&mut self, state: &GameState) -> Actions;
+}
+
+/// Returns the bot with the given name, or `None` if there is no such bot
+///
+/// Bots that make random decisions derive their own RNG from `seed`, so they don't affect the
+/// randomness of the game
+pub fn bot_by_name(name: &str, seed: u64) -> Option<Box<dyn Policy>> {
+    match name {
+        "idle" => Some(Box::new(Idle)),
+        "spinner" => Some(Box::new(Spinner)),
+        "random" => Some(Box::new(RandomBot::new(seed))),
+        "hunter" => Some(Box::new(Hunter)),
+        _ => None,
+    }
+}
+
+/// Does nothing at all, so the rocket just flies straight ahead
+pub struct Idle;
+
+impl Policy for Idle {
+    fn actions(&mut self, _: &GameState) -> Actions {
+        Actions::default()
+    }
+}
+
+/// Turns around in circles while shooting
+pub struct Spinner;
+
+impl Policy for Spinner {
+    fn actions(&mut self, _: &GameState) -> Actions {
+        Actions {
+            rotation: -1.0,
+            shoot: true,
+            ..Actions::default()
+        }
+    }
+}
+
+/// Mashes random buttons, changing its mind a few times per second
+pub struct RandomBot {
+    rng: GameRng,
+    current: Actions,
+    updates_left: u32,
+}
+
+impl RandomBot {
+    pub fn new(seed: u64) -> RandomBot {
+        RandomBot {
+            // Use a different stream than the game, even when it has the same seed
+            rng: GameRng::seed_from_u64(!seed),
+            current: Actions::default(),
+            updates_left: 0,
+        }
+    }
+}
+
+impl Policy for RandomBot {
+    fn actions(&mut self, _: &GameState) -> Actions {
+        if self.updates_left == 0 {
+            let rotation = self.rng.gen_range(0..3);
+            self.current = Actions::from_buttons(
+                rotation == 1,
+                rotation == 2,
+                self.rng.gen_bool(0.3),
+                self.rng.gen_bool(0.7),
+            );
+            self.updates_left = self.rng.gen_range(10..60);
+        }
+
+        self.updates_left -= 1;
+        self.current
+    }
+}
+
+/// Turns towards the nearest enemy (the shortest way around the world) and shoots at it
+pub struct Hunter;
+
+impl Policy for Hunter {
+    fn actions(&mut self, state: &GameState) -> Actions {
+        let player = &state.world.player;
+        let position = player.position();
+        let size = state.world.size;
+        // `total_cmp` puts NaN distances last, instead of panicking on them
+        let nearest = state.world.enemies.iter().min_by(|a, b| {
+            let a = torus::squared_distance(position, a.position(), size);
+            let b = torus::squared_distance(position, b.position(), size);
+            a.total_cmp(&b)
+        });
+
+        let target = match nearest {
+            Some(enemy) => enemy.position(),
+            None => return Actions::default(),
+        };
+
+        // The angle we need to turn, normalized between -PI and PI
+        let desired = torus::angle_to(position, target, size);
+        let delta = util::angle_difference(player.direction(), desired);
+
+        // Turn slower as we get close to the right direction, so we don't overshoot it
+        Actions {
+            rotation: (delta * 5.0).clamp(-1.0, 1.0),
+            thrust: 0.0,
+            shoot: delta.abs() < 0.3,
+        }
+    }
+}
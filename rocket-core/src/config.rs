@@ -0,0 +1,322 @@
+//! The tuning of the game, which can be changed without recompiling
+//!
+//! A `GameConfig` is usually read from a TOML file, where every section and value is optional.
+//! Values that are left out get their default, which is the value the game has always used. The
+//! `rocket.toml` file at the root of the repository lists every value with its default.
+//!
+//! Unknown sections and values are rejected, so that typos don't go unnoticed. Frontends that keep
+//! their own sections in the same file (like the key bindings of the `rocket` binary) must remove
+//! them before the rest is parsed here.
+
+use std::f32;
+use std::io::{self, Read};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::PowerupKind;
+
+/// Everything that can be tuned about the game
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub player: PlayerConfig,
+    pub gun: GunConfig,
+    pub enemies: EnemyConfig,
+    pub powerups: PowerupConfig,
+    pub lives: LivesConfig,
+}
+
+/// The tuning of the player's rocket
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    /// Rotation speed, in radians per second
+    pub rotate_speed: f32,
+    /// Radius around the player in which enemies can't spawn, and that a shield clears
+    pub grace_area: f32,
+}
+
+impl Default for PlayerConfig {
+    fn default() -> PlayerConfig {
+        PlayerConfig {
+            rotate_speed: 2.0 * f32::consts::PI,
+            grace_area: 200.0,
+        }
+    }
+}
+
+/// The tuning of the player's gun and its bullets
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GunConfig {
+    /// How fast the gun shoots while the trigger is held
+    pub bullets_per_second: f32,
+    /// The temperature gained by each shot, the gun overheats at a temperature of 1
+    pub heat_per_shot: f32,
+    /// The temperature lost per second while the gun works
+    pub natural_cool_down_rate: f32,
+    /// The temperature lost per second after overheating, until the gun is cold again
+    pub overheat_cool_down_rate: f32,
+    /// The distance that bullets travel before disappearing
+    pub bullet_distance: f32,
+}
+
+impl Default for GunConfig {
+    fn default() -> GunConfig {
+        GunConfig {
+            bullets_per_second: 30.0,
+            heat_per_shot: 0.025,
+            natural_cool_down_rate: 0.2,
+            overheat_cool_down_rate: 0.4,
+            bullet_distance: 384.0,
+        }
+    }
+}
+
+/// The tuning of the enemies
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemyConfig {
+    /// Speed at the start of the game, in pixels per second
+    pub speed: f32,
+    /// The score gained for each destroyed enemy
+    pub score: u32,
+}
+
+impl Default for EnemyConfig {
+    fn default() -> EnemyConfig {
+        EnemyConfig {
+            speed: 100.0,
+            score: 10,
+        }
+    }
+}
+
+/// The tuning of the powerups
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerupConfig {
+    /// How many seconds a powerup lasts once collected
+    pub duration: f32,
+    /// How many seconds a powerup stays in the world before disappearing
+    pub ttl: f32,
+    /// How often each kind of powerup spawns
+    pub weights: PowerupWeights,
+}
+
+impl Default for PowerupConfig {
+    fn default() -> PowerupConfig {
+        PowerupConfig {
+            duration: 10.0,
+            ttl: 10.0,
+            weights: PowerupWeights::default(),
+        }
+    }
+}
+
+/// The rarity of each kind of powerup: the chance that a kind spawns is its weight divided by the
+/// sum of all weights, so a kind with a weight of 0 never spawns
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PowerupWeights {
+    pub shield: f32,
+    pub time_slow: f32,
+    pub triple_shot: f32,
+    pub homing_missiles: f32,
+    pub coolant: f32,
+    pub smart_bomb: f32,
+    pub magnet: f32,
+    pub extra_life: f32,
+}
+
+impl PowerupWeights {
+    /// Returns the weight of the given kind of powerup
+    pub fn weight(&self, kind: PowerupKind) -> f32 {
+        match kind {
+            PowerupKind::Shield => self.shield,
+            PowerupKind::TimeSlow => self.time_slow,
+            PowerupKind::TripleShot => self.triple_shot,
+            PowerupKind::HomingMissiles => self.homing_missiles,
+            PowerupKind::Coolant => self.coolant,
+            PowerupKind::SmartBomb => self.smart_bomb,
+            PowerupKind::Magnet => self.magnet,
+            PowerupKind::ExtraLife => self.extra_life,
+        }
+    }
+}
+
+impl Default for PowerupWeights {
+    fn default() -> PowerupWeights {
+        PowerupWeights {
+            shield: 3.0,
+            time_slow: 3.0,
+            triple_shot: 3.0,
+            homing_missiles: 2.0,
+            coolant: 2.0,
+            smart_bomb: 1.0,
+            magnet: 2.0,
+            extra_life: 0.5,
+        }
+    }
+}
+
+/// The tuning of the player's lives
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LivesConfig {
+    /// The lives at the start of the game, including the rocket in play
+    pub starting: u32,
+    /// An extra life is gained every time the score reaches a multiple of this (0 means never)
+    pub extra_life_every: u32,
+    /// How many seconds it takes for a destroyed rocket to come back
+    pub respawn_delay: f32,
+    /// How many seconds a respawned rocket can't be destroyed
+    pub invulnerability: f32,
+}
+
+impl Default for LivesConfig {
+    fn default() -> LivesConfig {
+        LivesConfig {
+            starting: 3,
+            extra_life_every: 1000,
+            respawn_delay: 2.0,
+            invulnerability: 3.0,
+        }
+    }
+}
+
+impl GameConfig {
+    /// Parses a config from TOML, and checks that its values make sense
+    pub fn from_toml(toml: &str) -> io::Result<GameConfig> {
+        let config: GameConfig = toml::from_str(toml)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        config
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(config)
+    }
+
+    /// Reads a config in TOML format, see `from_toml`
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<GameConfig> {
+        let mut toml = String::new();
+        reader.read_to_string(&mut toml)?;
+        GameConfig::from_toml(&toml)
+    }
+
+    /// Checks that the values of the config make sense, returning a description of the first
+    /// value that doesn't
+    pub fn validate(&self) -> Result<(), String> {
+        positive("player.rotate_speed", self.player.rotate_speed, MAX_RATE)?;
+        non_negative("player.grace_area", self.player.grace_area, MAX_DISTANCE)?;
+
+        positive("gun.bullets_per_second", self.gun.bullets_per_second, MAX_RATE)?;
+        // A single shot can't heat the gun beyond overheating
+        positive("gun.heat_per_shot", self.gun.heat_per_shot, 1.0)?;
+        non_negative("gun.natural_cool_down_rate", self.gun.natural_cool_down_rate, MAX_RATE)?;
+        // An overheated gun that doesn't cool down would never shoot again
+        positive("gun.overheat_cool_down_rate", self.gun.overheat_cool_down_rate, MAX_RATE)?;
+        positive("gun.bullet_distance", self.gun.bullet_distance, MAX_DISTANCE)?;
+
+        positive("enemies.speed", self.enemies.speed, MAX_DISTANCE)?;
+        at_most("enemies.score", self.enemies.score, MAX_SCORE)?;
+
+        positive("powerups.duration", self.powerups.duration, MAX_SECONDS)?;
+        positive("powerups.ttl", self.powerups.ttl, MAX_SECONDS)?;
+        let weights = &self.powerups.weights;
+        let named_weights = [
+            ("shield", weights.shield),
+            ("time_slow", weights.time_slow),
+            ("triple_shot", weights.triple_shot),
+            ("homing_missiles", weights.homing_missiles),
+            ("coolant", weights.coolant),
+            ("smart_bomb", weights.smart_bomb),
+            ("magnet", weights.magnet),
+            ("extra_life", weights.extra_life),
+        ];
+        for &(name, weight) in &named_weights {
+            non_negative(&format!("powerups.weights.{}", name), weight, MAX_WEIGHT)?;
+        }
+        if named_weights.iter().all(|&(_, weight)| weight == 0.0) {
+            return Err("powerups.weights must give at least one kind a weight greater than 0".to_string());
+        }
+
+        if self.lives.starting == 0 {
+            return Err("lives.starting must be at least 1".to_string());
+        }
+        at_most("lives.starting", self.lives.starting, MAX_LIVES)?;
+        non_negative("lives.respawn_delay", self.lives.respawn_delay, MAX_SECONDS)?;
+        non_negative("lives.invulnerability", self.lives.invulnerability, MAX_SECONDS)?;
+        Ok(())
+    }
+}
+
+// The largest values that make sense, way beyond anything playable, but small enough that
+// everything derived from them (like the time between shots, or the chance of each powerup) stays
+// within range
+pub(crate) const MAX_SECONDS: f32 = 3600.0;
+const MAX_RATE: f32 = 1000.0;
+const MAX_DISTANCE: f32 = 100_000.0;
+const MAX_WEIGHT: f32 = 1_000_000.0;
+const MAX_SCORE: u32 = 10_000;
+const MAX_LIVES: u32 = 1000;
+
+fn positive(name: &str, value: f32, max: f32) -> Result<(), String> {
+    if value.is_finite() && value > 0.0 && value <= max {
+        Ok(())
+    } else {
+        Err(format!("{} must be a number greater than 0 and at most {}, but it is {}", name, max, value))
+    }
+}
+
+pub(crate) fn non_negative(name: &str, value: f32, max: f32) -> Result<(), String> {
+    if value.is_finite() && value >= 0.0 && value <= max {
+        Ok(())
+    } else {
+        Err(format!("{} must be a number between 0 and {}, but it is {}", name, max, value))
+    }
+}
+
+pub(crate) fn at_most(name: &str, value: u32, max: u32) -> Result<(), String> {
+    if value <= max {
+        Ok(())
+    } else {
+        Err(format!("{} must be at most {}, but it is {}", name, max, value))
+    }
+}
+
+#[test]
+fn test_config_from_toml() {
+    assert_eq!(GameConfig::from_toml("").unwrap(), GameConfig::default());
+    assert_eq!(GameConfig::from_toml(include_str!("../../rocket.toml")).unwrap(), GameConfig::default());
+
+    let config = GameConfig::from_toml("[gun]\nbullets_per_second = 10.0\n[enemies]\nscore = 25").unwrap();
+    assert_eq!(config.gun.bullets_per_second, 10.0);
+    assert_eq!(config.gun.heat_per_shot, GunConfig::default().heat_per_shot);
+    assert_eq!(config.enemies.score, 25);
+
+    let error = GameConfig::from_toml("[gun]\nbullets_per_second = -1.0").unwrap_err();
+    assert!(error.to_string().contains("gun.bullets_per_second"));
+    assert!(GameConfig::from_toml("[lives]\nstarting = 0").is_err());
+    let error = GameConfig::from_toml("[powerups.weights]\nmagnet = -1.0").unwrap_err();
+    assert!(error.to_string().contains("powerups.weights.magnet"));
+    let no_weights = "[powerups.weights]\nshield = 0.0\ntime_slow = 0.0\ntriple_shot = 0.0\nhoming_missiles = 0.0\n\
+                      coolant = 0.0\nsmart_bomb = 0.0\nmagnet = 0.0\nextra_life = 0.0";
+    assert!(GameConfig::from_toml(no_weights).is_err());
+    assert!(GameConfig::from_toml("[enemies]\nspeeed = 1.0").is_err());
+    assert!(GameConfig::from_toml("[controls]\nshoot = [\"J\"]").is_err());
+    assert!(GameConfig::from_toml("[gunn]\nbullets_per_second = 10.0").is_err());
+    assert!(GameConfig::from_toml("[enemies]\nspeed = \"fast\"").is_err());
+
+    // Values that are too big (or not numbers at all) would break the game later on
+    let error = |toml: &str| GameConfig::from_toml(toml).unwrap_err().to_string();
+    assert!(error("[gun]\nbullets_per_second = 1e30").contains("gun.bullets_per_second"));
+    assert!(error("[gun]\nbullets_per_second = inf").contains("gun.bullets_per_second"));
+    assert!(error("[player]\nrotate_speed = nan").contains("player.rotate_speed"));
+    assert!(error("[gun]\nheat_per_shot = 2.0").contains("gun.heat_per_shot"));
+    assert!(error("[enemies]\nspeed = 1e9").contains("enemies.speed"));
+    assert!(error("[lives]\nrespawn_delay = 1e30").contains("lives.respawn_delay"));
+    assert!(error("[powerups]\nduration = 1e30").contains("powerups.duration"));
+    assert!(error("[powerups.weights]\nshield = 3e38\ntime_slow = 3e38").contains("powerups.weights.shield"));
+    assert!(error("[enemies]\nscore = 4000000000").contains("enemies.score"));
+    assert!(error("[lives]\nstarting = 4000000000").contains("lives.starting"));
+}
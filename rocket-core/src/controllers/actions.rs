@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Active actions (toggled by user input)
+///
+/// Rotation and thrust are analog, so they can be driven by a gamepad. The keyboard simply uses
+/// their extremes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Actions {
+    /// How fast to rotate, from -1 (full speed to the left) to 1 (full speed to the right)
+    pub rotation: f32,
+    /// How much to boost, from 0 (cruising speed) to 1 (double speed)
+    pub thrust: f32,
+    pub shoot: bool,
+}
+
+impl Actions {
+    /// Returns the actions resulting from pressing the given digital buttons
+    ///
+    /// When both rotation buttons are pressed, rotating left wins
+    pub fn from_buttons(rotate_left: bool, rotate_right: bool, boost: bool, shoot: bool) -> Actions {
+        let rotation = if rotate_left {
+            -1.0
+        } else if rotate_right {
+            1.0
+        } else {
+            0.0
+        };
+
+        Actions {
+            rotation,
+            thrust: if boost { 1.0 } else { 0.0 },
+            shoot,
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use std::time::Duration;
+
+use crate::{
+    controllers::{
+        Event,
+        time::{TimeController, Timeout},
+    },
+    game_state::GameState,
+    geometry::{torus, Collide, Point, Position, Size},
+    models::{BossPart, Enemy, EnemyKind, Particle, PowerupKind},
+    spatial_hash::SpatialHash,
+    util,
+};
+
+// The size of the cells of the grids used to find nearby objects, see `SpatialHash`
+const CELL_SIZE: f32 = 64.0;
+// The seconds that a player can't be destroyed after their shield broke on the boss
+const SHIELD_BREAK_INVULNERABILITY: f32 = 1.0;
+
+pub struct CollisionsController;
+
+impl CollisionsController {
+    pub fn handle_collisions(state: &mut GameState, time_controller: &mut TimeController, events: &mut Vec<Event>) {
+        // The kinds of the enemies destroyed during this update
+        let mut killed_enemies = Vec::new();
+
+        CollisionsController::handle_bullet_collisions(state, &mut killed_enemies, events);
+
+        let got_powerup =
+            CollisionsController::handle_powerup_collisions(state, events);
+        if let Some(kind) = got_powerup {
+            state.powerups_collected += 1;
+
+            match kind {
+                PowerupKind::SmartBomb => {
+                    CollisionsController::detonate_smart_bomb(state, &mut killed_enemies, events);
+                }
+                PowerupKind::ExtraLife => {
+                    state.lives += 1;
+                    events.push(Event::LifeGained);
+                }
+                _ => {
+                    // Powerups run out after a while, each on its own (collecting a powerup that
+                    // is still active extends it)
+                    let duration = Duration::from_secs_f32(state.config.powerups.duration);
+                    state.world.player.activate_powerup(kind, time_controller.current_time(), duration);
+                    time_controller
+                        .schedule_timeout(duration, Timeout::RemovePowerup(kind));
+
+                    if kind == PowerupKind::Coolant {
+                        state.world.player.gun.reset();
+                    }
+                }
+            }
+        }
+
+        // If the player died they lose a life. While they have lives left we set a timeout after
+        // which they respawn, otherwise one after which a game over message will appear, and the
+        // user will be able to restart.
+        let player_died =
+            CollisionsController::handle_player_collisions(state, &mut killed_enemies, events);
+        if player_died {
+            state.lives -= 1;
+            if state.is_game_over() {
+                let offset = Duration::from_secs(2);
+                time_controller
+                    .schedule_timeout(offset, Timeout::ShowGameOverScreen);
+            } else {
+                let offset = Duration::from_secs_f32(state.config.lives.respawn_delay);
+                time_controller
+                    .schedule_timeout(offset, Timeout::RespawnPlayer);
+            }
+        }
+
+        state.enemies_killed += killed_enemies.len() as u32;
+        let points: u32 = killed_enemies.iter().map(|kind| state.config.enemies.score * kind.score_factor()).sum();
+        let extra_lives = state.add_score(points);
+        events.extend((0..extra_lives).map(|_| Event::LifeGained));
+    }
+
+    /// Handles collisions between the bullets and the enemies (and their bullets, and the boss)
+    ///
+    /// When an enemy is reached by a bullet, the bullet will be removed and the enemy loses a hit
+    /// point. Enemies without hit points left are removed (leaving their fragments behind, if
+    /// any) and their kind is added to `killed_enemies`. Bullets that reach an enemy bullet
+    /// remove it, and are removed themselves. The boss only takes damage on its weak points, its
+    /// body just stops bullets.
+    fn handle_bullet_collisions(state: &mut GameState, killed_enemies: &mut Vec<EnemyKind>, events: &mut Vec<Event>) {
+        // We introduce a scope to shorten the lifetime of the borrows below
+        {
+            let bullets = &mut state.world.bullets;
+            let enemies = &mut state.world.enemies;
+            let enemy_bullets = &mut state.world.enemy_bullets;
+            let particles = &mut state.world.particles;
+            let boss = &mut state.world.boss;
+            let mut fragments = Vec::new();
+            let mut boss_destroyed = false;
+
+            // Only the enemies (and enemy bullets) near a bullet are checked, see `SpatialHash`.
+            // The ones that get destroyed are removed once all bullets are done.
+            let size = state.world.size;
+            let enemy_hash = SpatialHash::from_objects(size, CELL_SIZE, enemies.iter().map(|e| (e.position(), e.radius())));
+            let enemy_bullet_hash =
+                SpatialHash::from_objects(size, CELL_SIZE, enemy_bullets.iter().map(|b| (b.position(), b.radius())));
+            let mut destroyed_enemies = vec![false; enemies.len()];
+            let mut destroyed_enemy_bullets = vec![false; enemy_bullets.len()];
+
+            util::fast_retain(bullets, |bullet| {
+                // Bullets are fast, so they hit whatever they touched along their path during the
+                // last update (which might cross the edges of the world), not just where they are
+                let (start, end) = bullet.path(size);
+                let middle = start.lerp(end, 0.5);
+                let reach = start.squared_distance_to(end).sqrt() / 2.0 + bullet.radius();
+                let swept = |position: Point, radius: f32| {
+                    torus::intersect_segment_circle(start, end, position, radius + bullet.radius(), size)
+                };
+
+                // Hit the first enemy that collides with a bullet (if any)
+                // If that destroys it, add an explosion on its place
+                let hit_enemy = first_hit(&enemy_hash, middle, reach, |i| !destroyed_enemies[i] && swept(enemies[i].position(), enemies[i].radius()));
+                let hit_enemy_bullet = || {
+                    first_hit(&enemy_bullet_hash, middle, reach, |i| {
+                        !destroyed_enemy_bullets[i] && swept(enemy_bullets[i].position(), enemy_bullets[i].radius())
+                    })
+                };
+
+                if let Some(index) = hit_enemy {
+                    let enemy = &mut enemies[index];
+                    if enemy.hit() {
+                        destroyed_enemies[index] = true;
+                        util::make_explosion(particles, &enemy.position(), 10);
+                        fragments.extend(enemy.fragments());
+                        killed_enemies.push(enemy.kind);
+                        events.push(Event::EnemyDestroyed);
+                    }
+                    false
+                } else if let Some(index) = hit_enemy_bullet() {
+                    destroyed_enemy_bullets[index] = true;
+                    util::make_explosion(particles, &enemy_bullets[index].position(), 3);
+                    false
+                } else if let Some(part) = boss.as_ref().and_then(|boss| boss.part_swept_by(start, end, bullet.radius(), size)) {
+                    util::make_explosion(particles, &bullet.position(), 3);
+                    if part == BossPart::WeakPoint && !boss_destroyed {
+                        boss_destroyed = boss.as_mut().unwrap().hit();
+                    }
+                    false
+                } else {
+                    true
+                }
+            });
+
+            let mut destroyed = destroyed_enemies.into_iter();
+            enemies.retain(|_| !destroyed.next().unwrap());
+            let mut destroyed = destroyed_enemy_bullets.into_iter();
+            enemy_bullets.retain(|_| !destroyed.next().unwrap());
+            enemies.extend(fragments);
+
+            // The boss goes out with a bang, at its body and each of its weak points
+            if boss_destroyed {
+                let boss = boss.take().unwrap();
+                util::make_explosion(particles, &boss.position(), 40);
+                for point in &boss.weak_points() {
+                    util::make_explosion(particles, point, 15);
+                }
+                events.push(Event::BossDestroyed);
+            }
+        }
+    }
+
+    /// Handles collisions between the player and powerups
+    ///
+    /// Returns the kind of the powerup that the player picked up, if any
+    fn handle_powerup_collisions(state: &mut GameState, events: &mut Vec<Event>) -> Option<PowerupKind> {
+        let mut gained_powerup = None;
+        let player = &mut state.world.player;
+        let powerups = &mut state.world.powerups;
+
+        if !player.is_dead {
+            let size = state.world.size;
+            let hash = SpatialHash::from_objects(size, CELL_SIZE, powerups.iter().map(|p| (p.position(), p.radius())));
+            if let Some(index) = first_hit(&hash, player.position(), player.radius(), |i| powerups[i].collides_with_wrapping(player, size)) {
+                gained_powerup = Some(powerups[index].kind);
+                powerups.remove(index);
+
+                events.push(Event::PowerupGained);
+            }
+        }
+
+        gained_powerup
+    }
+
+    /// Handles collisions between the player and the enemies (and their bullets, and the boss)
+    /// This function will return true if the player died
+    ///
+    /// A player that has just respawned flies through the enemies unharmed
+    fn handle_player_collisions(
+        state: &mut GameState,
+        killed_enemies: &mut Vec<EnemyKind>,
+        events: &mut Vec<Event>,
+    ) -> bool {
+        let mut player_died = false;
+        let player = &mut state.world.player;
+        if player.is_dead || player.is_invulnerable() {
+            return false;
+        }
+
+        // An enemy bullet that hits the player is gone, whether the player survives it or not
+        let size = state.world.size;
+        let hit_by_bullet = match state.world.enemy_bullets.iter().position(|b| player.collides_with_wrapping(b, size)) {
+            Some(index) => {
+                state.world.enemy_bullets.swap_remove(index);
+                true
+            }
+            None => false,
+        };
+
+        let enemies = &state.world.enemies;
+        let enemy_hash =
+            SpatialHash::from_objects(size, CELL_SIZE, enemies.iter().map(|e| (e.position(), e.radius())));
+        let hit_by_enemy = first_hit(&enemy_hash, player.position(), player.radius(), |i| player.collides_with_wrapping(&enemies[i], size)).is_some();
+        let hit_by_boss = state.world.boss.as_ref().is_some_and(|boss| boss.part_hit_by(player, size).is_some());
+        if hit_by_bullet || hit_by_boss || hit_by_enemy {
+            // Remove shield powerup from player, also killing any enemies (and removing any enemy
+            // bullets) within close range
+            if player.has_powerup(PowerupKind::Shield) {
+                player.remove_powerup(PowerupKind::Shield);
+
+                // The boss survives the shield, so give the player a moment to get away from it
+                if hit_by_boss {
+                    player.invulnerability = SHIELD_BREAK_INVULNERABILITY;
+                }
+
+                let grace_area = state.config.player.grace_area;
+                let position = player.position();
+                util::fast_retain(&mut state.world.enemy_bullets, |b| {
+                    !torus::intersect_circle(b.position(), position, grace_area, size)
+                });
+
+                let enemies = &mut state.world.enemies;
+                let particles = &mut state.world.particles;
+                CollisionsController::remove_surrounding_enemies(
+                    enemies,
+                    &enemy_hash,
+                    particles,
+                    killed_enemies,
+                    position,
+                    grace_area,
+                    size,
+                );
+                events.push(Event::EnemyDestroyed);
+            } else {
+                // Make an explosion where the player was
+                let ppos = player.position();
+                util::make_explosion(&mut state.world.particles, &ppos, 16);
+                // Mark the player as dead (to stop drawing it on screen)
+                player_died = true;
+                player.is_dead = true;
+                events.push(Event::PlayerDestroyed);
+            }
+        }
+
+        player_died
+    }
+
+    /// Destroys every enemy and enemy bullet in the world (but not the boss)
+    fn detonate_smart_bomb(state: &mut GameState, killed_enemies: &mut Vec<EnemyKind>, events: &mut Vec<Event>) {
+        // The explosion is only played when it destroys at least one enemy
+        if !state.world.enemies.is_empty() {
+            events.push(Event::EnemyDestroyed);
+        }
+
+        let particles = &mut state.world.particles;
+        for enemy in state.world.enemies.drain(..) {
+            util::make_explosion(particles, &enemy.position(), 10);
+            killed_enemies.push(enemy.kind);
+        }
+        for bullet in state.world.enemy_bullets.drain(..) {
+            util::make_explosion(particles, &bullet.position(), 3);
+        }
+    }
+
+    /// Removes the enemies within `radius` of `point` (reaching across the edges of a world of the
+    /// given size), regardless of their hit points and without leaving fragments behind
+    ///
+    /// `enemy_hash` must have been built from `enemies`
+    fn remove_surrounding_enemies(
+        enemies: &mut Vec<Enemy>,
+        enemy_hash: &SpatialHash,
+        particles: &mut Vec<Particle>,
+        killed_enemies: &mut Vec<EnemyKind>,
+        point: Point,
+        radius: f32,
+        size: Size,
+    ) {
+        let mut surrounding = vec![false; enemies.len()];
+        enemy_hash.query(point, radius, |i| {
+            let enemy_pos = enemies[i].position();
+            if torus::intersect_circle(enemy_pos, point, radius, size) {
+                util::make_explosion(particles, &enemy_pos, 10);
+                killed_enemies.push(enemies[i].kind);
+                surrounding[i] = true;
+            }
+        });
+
+        let mut surrounding = surrounding.into_iter();
+        enemies.retain(|_| !surrounding.next().unwrap());
+    }
+}
+
+// Returns the lowest index of the objects in `hash` within `radius` of `position` for which `hits`
+// is true, so that the result doesn't depend on the layout of the grid
+fn first_hit(hash: &SpatialHash, position: Point, radius: f32, mut hits: impl FnMut(usize) -> bool) -> Option<usize> {
+    let mut first = None;
+    hash.query(position, radius, |index| {
+        if first.is_none_or(|first| index < first) && hits(index) {
+            first = Some(index);
+        }
+    });
+    first
+}
@@ -0,0 +1,31 @@
+//! This module contains the game logic
+//!
+//! There are two main controllers: collisions and time. Input is handled by the front end, which
+//! translates it into the `Actions` that the time controller consumes
+
+mod actions;
+mod collisions;
+mod time;
+
+pub use self::actions::Actions;
+pub use self::collisions::CollisionsController;
+pub use self::time::{
+    FixedTimestep, TimeController, Timeout, TICKS_PER_SECOND, TIMESTEP,
+};
+
+/// Something noteworthy that happened during the game, used to trigger sounds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Event {
+    PlayerDestroyed,
+    LifeGained,
+    EnemyDestroyed,
+    PowerupGained,
+    ShotFired,
+    EnemyShotFired,
+    EnemySpawned,
+    WaveStarted,
+    WaveCleared,
+    BossArrived,
+    BossDestroyed,
+    GameStart
+}
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+/// The amount of times per second that the game is updated, regardless of the frame rate
+pub const TICKS_PER_SECOND: u32 = 120;
+
+/// The amount of time that passes in the game on every update
+pub const TIMESTEP: Duration = Duration::from_nanos(1_000_000_000 / TICKS_PER_SECOND as u64);
+
+/// If a frame takes longer than this (e.g. because the window is being dragged around), the extra
+/// time is dropped instead of trying to catch up with a burst of updates
+const MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+
+/// A `FixedTimestep` turns the variable time between frames into a whole number of updates of
+/// `TIMESTEP` each, so the game behaves the same on slow and fast machines
+///
+/// The time that is left over is kept for the next frame, and can be used to interpolate the
+/// positions of the objects on the screen
+#[derive(Default)]
+pub struct FixedTimestep {
+    accumulator: Duration,
+}
+
+impl FixedTimestep {
+    pub fn new() -> FixedTimestep {
+        FixedTimestep::default()
+    }
+
+    /// Adds the duration of the last frame, and returns the amount of updates that should run
+    pub fn advance(&mut self, frame_time: Duration) -> u32 {
+        self.accumulator += frame_time.min(MAX_FRAME_TIME);
+
+        let mut ticks = 0;
+        while self.accumulator >= TIMESTEP {
+            self.accumulator -= TIMESTEP;
+            ticks += 1;
+        }
+        ticks
+    }
+
+    /// Returns how far we are between the last update and the next one, from 0.0 to 1.0
+    pub fn alpha(&self) -> f32 {
+        self.accumulator.as_secs_f32() / TIMESTEP.as_secs_f32()
+    }
+}
+
+#[test]
+fn test_fixed_timestep() {
+    let mut timestep = FixedTimestep::new();
+    assert_eq!(timestep.advance(TIMESTEP / 2), 0);
+    assert!((timestep.alpha() - 0.5).abs() < 1e-3);
+    assert_eq!(timestep.advance(TIMESTEP * 2), 2);
+    assert_eq!(timestep.advance(Duration::from_secs(10)), MAX_FRAME_TIME.as_nanos() as u32 / TIMESTEP.as_nanos() as u32);
+}
@@ -0,0 +1,513 @@
+mod fixed_timestep;
+mod timeout;
+mod timeout_queue;
+mod timer;
+
+use std::f32;
+use std::time::Duration;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::GameConfig,
+    controllers::{
+        Event,
+        Actions,
+    },
+    game_state::GameState,
+    geometry::{torus, Advance, Interpolate, Point, Position, Size, Vector},
+    models::{Boss, BossAttack, Bullet, Enemy, EnemyBullet, EnemyKind, Particle, Player, Powerup, PowerupKind},
+    util,
+    waves::{Spawn, SpawnPattern, WaveEvent},
+};
+
+use self::timer::Timer;
+pub use self::fixed_timestep::{FixedTimestep, TICKS_PER_SECOND, TIMESTEP};
+pub use self::timeout::Timeout;
+use self::timeout_queue::TimeoutQueue;
+
+// Constants related to time
+const TRAIL_PARTICLES_PER_SECOND: f32 = 20.0;
+const TRAIL_PARTICLE_RATE: f32 = 1.0 / TRAIL_PARTICLES_PER_SECOND;
+
+const POWERUP_SPAWNS_PER_SECOND: f32 = 1.0 / 10.0; // every ~10 seconds
+const POWERUP_SPAWN_RATE: f32 = 1.0 / POWERUP_SPAWNS_PER_SECOND;
+
+// Constants related to movement
+// Speed is measured in pixels per second
+// Rotation speed is measured in radians per second
+const ADVANCE_SPEED: f32 = 200.0;
+const BULLET_SPEED: f32 = 500.0;
+const ENEMY_BULLET_SPEED: f32 = 300.0;
+// The distance that enemy bullets travel before disappearing
+const ENEMY_BULLET_DISTANCE: f32 = 600.0;
+// Extra speed of the enemies per point of difficulty
+const DIFFICULTY_SPEED: f32 = 60.0;
+// The enemies' base speed is multiplied by this while the TimeSlow powerup is active
+const TIME_SLOW_FACTOR: f32 = 0.25;
+const STAR_BASE_SPEED: f32 = 50.0;
+// How fast homing bullets turn, in radians per second
+const HOMING_TURN_SPEED: f32 = 4.0;
+// The Magnet powerup pulls powerups within this distance of the player, at this speed
+const MAGNET_RADIUS: f32 = 250.0;
+const MAGNET_SPEED: f32 = 250.0;
+
+#[derive(Serialize, Deserialize)]
+pub struct TimeController {
+    /// The duration of the current game, since the last restart
+    current_time: Duration,
+    /// A timer to trigger creation of trail particles
+    trail_timer: Timer,
+    /// A timer to trigger creation of bullets
+    shoot_timer: Timer,
+    /// A timer to spawn powerups
+    powerup_timer: Timer,
+    /// Scheduled events that should happen in the future
+    scheduled_timeouts: TimeoutQueue,
+}
+
+impl TimeController {
+    /// Returns a time controller for a new game, tuned by `config`
+    pub fn new(config: &GameConfig) -> TimeController {
+        TimeController {
+            current_time: Duration::from_secs(0),
+            trail_timer: Timer::from_seconds(TRAIL_PARTICLE_RATE),
+            shoot_timer: Timer::from_seconds(1.0 / config.gun.bullets_per_second),
+            powerup_timer: Timer::from_seconds(POWERUP_SPAWN_RATE),
+            scheduled_timeouts: TimeoutQueue::new(),
+        }
+    }
+
+    /// Returns an error if the timers don't match the ones of a game tuned by `config`, describing
+    /// the first problem
+    pub fn validate(&self, config: &GameConfig) -> Result<(), String> {
+        let expected = TimeController::new(config);
+        let timers = [
+            ("trail", &self.trail_timer, &expected.trail_timer),
+            ("shoot", &self.shoot_timer, &expected.shoot_timer),
+            ("powerup", &self.powerup_timer, &expected.powerup_timer),
+        ];
+        for (name, timer, expected) in timers.iter() {
+            timer
+                .validate(expected.interval(), self.current_time)
+                .map_err(|e| format!("invalid {} timer: {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    // Called when the game is reset
+    pub fn reset(&mut self, config: &GameConfig) {
+        *self = TimeController::new(config);
+    }
+
+    /// Returns the duration of the current game, since the last restart
+    pub fn current_time(&self) -> Duration {
+        self.current_time
+    }
+
+    pub fn schedule_timeout(&mut self, offset: Duration, timeout: Timeout) {
+        self.scheduled_timeouts.push(self.current_time + offset, timeout);
+    }
+
+    /// Updates the game
+    ///
+    /// `dt` is the amount of time that has passed since the last update. The game behaves the
+    /// same regardless of how time is split into updates, but for reproducible games `dt` should
+    /// always be `TIMESTEP` (see `FixedTimestep`)
+    pub fn update_seconds<R: Rng>(
+        &mut self,
+        dt: Duration,
+        actions: &Actions,
+        state: &mut GameState,
+        events: &mut Vec<Event>,
+        rng: &mut R
+    ) {
+        self.current_time += dt;
+
+        let dt = util::duration_to_seconds(dt);
+
+        // Check if we have any events that are scheduled to run, and if so, run them now
+        while let Some(when) = self.scheduled_timeouts.peek() {
+            if when > self.current_time {
+                break;
+            }
+            self.scheduled_timeouts.pop().unwrap().handle(state, self.current_time);
+        }
+
+        // Remember where everything was, so it can be drawn in between updates
+        self.store_positions(state);
+
+        // Check if we have the "TimeSlow" powerup
+        let time_slow = state.world.player.has_powerup(PowerupKind::TimeSlow);
+
+        // Only modify player/powerups if player is alive
+        if !state.world.player.is_dead {
+            self.update_player(dt, actions, state);
+            self.update_powerups(dt, state, rng);
+        }
+
+        self.update_bullets(dt, actions, state, events);
+        self.update_particles(dt, state);
+        self.update_enemies(dt, state, events, time_slow, rng);
+        self.update_boss(dt, state, events, time_slow);
+        self.update_enemy_bullets(dt, state, time_slow);
+        self.update_stars(dt, state, time_slow);
+    }
+
+    // Stores the current position of every moving object
+    fn store_positions(&self, state: &mut GameState) {
+        let world = &mut state.world;
+        world.player.store_position();
+        world.bullets.iter_mut().for_each(Interpolate::store_position);
+        world.enemies.iter_mut().for_each(Interpolate::store_position);
+        world.enemy_bullets.iter_mut().for_each(Interpolate::store_position);
+        world.boss.iter_mut().for_each(Interpolate::store_position);
+        world.particles.iter_mut().for_each(Interpolate::store_position);
+        world.stars.iter_mut().for_each(Interpolate::store_position);
+    }
+
+    // Updates the position and rotation of the player
+    fn update_player(&mut self, dt: f32, actions: &Actions, state: &mut GameState) {
+        if !state.world.player.is_dead {
+            // Rotate proportionally to the rotation action
+            let rotation = actions.rotation.clamp(-1.0, 1.0);
+            *state.world.player.direction_mut() += state.config.player.rotate_speed * rotation * dt;
+
+            // Set speed (boosting up to twice as fast) and advance the player with wrap around
+            let thrust = actions.thrust.clamp(0.0, 1.0);
+            let speed = ADVANCE_SPEED * (1.0 + thrust);
+            state
+                .world
+                .player
+                .advance_wrapping(dt * speed, state.world.size);
+
+            // Cool down the player's gun
+            state.world.player.gun.cool_down(dt, &state.config.gun);
+
+            // Count down the invulnerability after respawning
+            let player = &mut state.world.player;
+            player.invulnerability = (player.invulnerability - dt).max(0.0);
+        }
+    }
+
+    // Adds, removes and updates the positions of bullets on screen
+    fn update_bullets(
+        &mut self,
+        dt: f32,
+        actions: &Actions,
+        state: &mut GameState,
+        events: &mut Vec<Event>,
+    ) {
+        // Add bullets - usually when the player shoots the gun heats up, if it has overheated the
+        // player can no longer shoot - unless they have the tripleshot powerup, which will work
+        // regardless of the gun's state. With the coolant powerup the gun doesn't heat up at all,
+        // and with the homing missiles powerup the bullets steer towards the enemies.
+        if !state.world.player.is_dead && actions.shoot {
+            let gun_config = state.config.gun;
+            let distance = gun_config.bullet_distance;
+            let homing = state.world.player.has_powerup(PowerupKind::HomingMissiles);
+            let coolant = state.world.player.has_powerup(PowerupKind::Coolant);
+            let new_bullet = |vector| if homing { Bullet::homing(vector, distance) } else { Bullet::new(vector, distance) };
+            self.shoot_timer.update(self.current_time, || {
+                // If the player has the TripleShot powerup, apply that here
+                if state.world.player.has_powerup(PowerupKind::TripleShot) {
+                    let pos = state.world.player.front();
+                    let dir = state.world.player.direction();
+                    state.world.bullets.extend_from_slice(&[
+                        new_bullet(Vector::new(pos, dir - f32::consts::PI / 6.0)),
+                        new_bullet(Vector::new(pos, dir)),
+                        new_bullet(Vector::new(pos, dir + f32::consts::PI / 6.0)),
+                    ]);
+                    events.push(Event::ShotFired);
+                }
+                // If there was no powerup, shoot normally
+                else if state.world.player.gun.is_available() {
+                    let pos = state.world.player.front();
+                    let dir = state.world.player.direction();
+                    state.world.bullets.push(new_bullet(Vector::new(pos, dir)));
+                    if !coolant {
+                        state.world.player.gun.heat_up(&gun_config);
+                    }
+
+                    events.push(Event::ShotFired);
+                }
+            });
+        } else {
+            self.shoot_timer.rest(self.current_time);
+        }
+
+        // Advance bullets, steering the homing ones towards the enemies and the boss' weak points
+        let world = &mut state.world;
+        for bullet in &mut world.bullets {
+            let enemies = world.enemies.iter().map(|enemy| enemy.position());
+            let weak_points = world.boss.iter().flat_map(|boss| boss.weak_points());
+            bullet.steer(enemies.chain(weak_points), dt * HOMING_TURN_SPEED, world.size);
+            bullet.update(dt * BULLET_SPEED, world.size);
+        }
+
+        // Remove bullets which have moved too far
+        util::fast_retain(&mut state.world.bullets, |b| !b.reached_max_distance());
+    }
+
+    fn update_powerups<R: Rng>(&mut self, dt: f32, state: &mut GameState, rng: &mut R) {
+        let magnet = state.world.player.has_powerup(PowerupKind::Magnet);
+        let player_position = state.world.player.position();
+        let size = state.world.size;
+        for powerup in &mut state.world.powerups {
+            powerup.update(dt);
+
+            // Pull nearby powerups towards the player, through the shortest way around the world
+            if magnet {
+                let delta = torus::displacement(powerup.position, player_position, size);
+                let distance = delta.length();
+                if distance > 0.0 && distance < MAGNET_RADIUS {
+                    let step = (dt * MAGNET_SPEED).min(distance);
+                    powerup.position = torus::normalize(powerup.position + delta.normalize() * step, size);
+                }
+            }
+        }
+
+        // Remove any expired powerups
+        util::fast_retain(&mut state.world.powerups, |p| p.ttl > 0.0);
+
+        // Add new powerups
+        let ttl = state.config.powerups.ttl;
+        let weights = state.config.powerups.weights;
+        self.powerup_timer.update(self.current_time, || {
+            state
+                .world
+                .powerups
+                .push(Powerup::random(rng, state.world.size, ttl, &weights));
+        });
+    }
+
+    // Updates or removes particles on screen, adds particles behind player
+    fn update_particles(&mut self, dt: f32, state: &mut GameState) {
+        for particle in &mut state.world.particles {
+            particle.update(dt);
+        }
+
+        // Remove old particles
+        util::fast_retain(&mut state.world.particles, |p| p.ttl > 0.0);
+
+        // Add new particles at the player's position, to leave a trail
+        if !state.world.player.is_dead {
+            self.trail_timer.update(self.current_time, || {
+                state.world.particles.push(Particle::new(
+                    state.world.player.vector.clone().invert(),
+                    0.5,
+                ));
+            });
+        }
+    }
+
+    // Updates positions of enemies, and spawns new ones as the waves go
+    fn update_enemies<R: Rng>(
+        &mut self,
+        dt: f32,
+        state: &mut GameState,
+        events: &mut Vec<Event>,
+        time_slow: bool,
+        rng: &mut R
+    ) {
+        // Let the wave director decide which enemies spawn, and where
+        let mut spawns = Vec::new();
+        let enemies_alive = state.world.enemies.len() + state.world.boss.iter().len();
+        let wave_event = state.waves.update(dt, enemies_alive, |spawn| spawns.push(spawn));
+
+        let grace_area = state.config.player.grace_area;
+        let player_position = state.world.player.position();
+        for spawn in spawns {
+            let kind = spawn.kind.unwrap_or_else(|| EnemyKind::random(rng, state.difficulty));
+            let vector = spawn_vector(&spawn, player_position, grace_area, state.world.size, rng);
+            state.world.enemies.push(Enemy::new(kind, vector));
+            events.push(Event::EnemySpawned);
+        }
+
+        match wave_event {
+            Some(WaveEvent::Started(wave)) => {
+                state.difficulty = state.waves.definitions().difficulty_per_wave * (wave - 1) as f32;
+                events.push(Event::WaveStarted);
+            }
+            Some(WaveEvent::Cleared { bonus, .. }) | Some(WaveEvent::BossDefeated { bonus }) => {
+                let extra_lives = state.add_score(bonus);
+                events.extend((0..extra_lives).map(|_| Event::LifeGained));
+                events.push(Event::WaveCleared);
+            }
+            Some(WaveEvent::BossArrived { health }) => {
+                // The boss shows up on the other side of the world, facing the player
+                let Size { width, height } = state.world.size;
+                let position = torus::normalize(player_position + Point::new(width, height) / 2.0, state.world.size);
+                let mut vector = Vector::new(position, 0.0);
+                vector.point_to(player_position);
+                state.world.boss = Some(Boss::new(vector, health));
+                events.push(Event::BossArrived);
+            }
+            None => (),
+        }
+
+        // Move enemies in the player's direction if player is alive, otherwise let them drift in
+        // the direction they're facing. Shooters only shoot while the player is alive.
+        let enemy_speed = state.config.enemies.speed;
+        let player_velocity = player_velocity(&state.world.player, dt, state.world.size);
+        for enemy in &mut state.world.enemies {
+            if !state.world.player.is_dead {
+                let base_speed = if time_slow {
+                    enemy_speed * TIME_SLOW_FACTOR
+                } else {
+                    enemy_speed
+                };
+                enemy.update(
+                    dt,
+                    base_speed + state.difficulty * DIFFICULTY_SPEED,
+                    state.world.player.position(),
+                    state.world.size,
+                );
+
+                let player_position = state.world.player.position();
+                let shot = enemy.shoot(dt, player_position, player_velocity, ENEMY_BULLET_SPEED, state.world.size);
+                if let Some(aim) = shot {
+                    state.world.enemy_bullets.push(EnemyBullet::new(aim, ENEMY_BULLET_DISTANCE));
+                    events.push(Event::EnemyShotFired);
+                }
+            } else {
+                enemy.advance(dt * enemy_speed * enemy.kind.speed_factor());
+            }
+        }
+    }
+
+    // Moves the boss (if there is one) and carries out its attacks, which only happen while the
+    // player is alive. The TimeSlow powerup slows the boss down, attacks included.
+    fn update_boss(&mut self, dt: f32, state: &mut GameState, events: &mut Vec<Event>, time_slow: bool) {
+        let world = &mut state.world;
+        let boss = match world.boss {
+            Some(ref mut boss) if !world.player.is_dead => boss,
+            _ => return,
+        };
+
+        let dt = if time_slow { dt * TIME_SLOW_FACTOR } else { dt };
+        for attack in boss.update(dt, world.player.position(), world.size) {
+            match attack {
+                BossAttack::Shoot(vector) => {
+                    world.enemy_bullets.push(EnemyBullet::new(vector, ENEMY_BULLET_DISTANCE));
+                }
+                BossAttack::Spawn(vector) => {
+                    world.enemies.push(Enemy::new(EnemyKind::Chaser, vector));
+                    events.push(Event::EnemySpawned);
+                }
+            }
+        }
+    }
+
+    // Advances enemy bullets, which are slowed down by the TimeSlow powerup like the enemies
+    fn update_enemy_bullets(&mut self, dt: f32, state: &mut GameState, time_slow: bool) {
+        let speed = if time_slow { ENEMY_BULLET_SPEED * TIME_SLOW_FACTOR } else { ENEMY_BULLET_SPEED };
+        for bullet in &mut state.world.enemy_bullets {
+            bullet.update(dt * speed, state.world.size);
+        }
+
+        util::fast_retain(&mut state.world.enemy_bullets, |b| !b.reached_max_distance());
+    }
+
+    // Advance stars, wrapping them around the view
+    fn update_stars(&mut self, dt: f32, state: &mut GameState, time_slow: bool) {
+        for star in &mut state.world.stars {
+            let base_speed = if time_slow { 20.0 } else { STAR_BASE_SPEED };
+            let speed = dt * base_speed * star.speed;
+            star.advance_wrapping(speed, state.world.size);
+        }
+    }
+}
+
+// Extra distance from the player's grace area at which enemies spawn in a ring
+const RING_MARGIN: f32 = 50.0;
+
+// Returns where an enemy spawns, and the direction it faces
+//
+// Enemies never spawn inside the player's grace area: if they would, their spawn point is pushed
+// to the edge of the area
+fn spawn_vector<R: Rng>(spawn: &Spawn, player: Point, grace_area: f32, size: Size, rng: &mut R) -> Vector {
+    let mut vector = match spawn.pattern {
+        SpawnPattern::Random => {
+            // We loop here, just in case the new enemy random position is exactly equal
+            // to the players current position, this would break our calculations below
+            loop {
+                let vector = Vector::random(rng, size);
+                if vector.position != player {
+                    break vector;
+                }
+            }
+        }
+        SpawnPattern::Ring => {
+            // Facing the player, from evenly spread angles
+            let angle = 2.0 * f32::consts::PI * spawn.index as f32 / spawn.count as f32;
+            let radius = grace_area + RING_MARGIN;
+            let position = torus::normalize(player + Point::from_angle(angle) * radius, size);
+            Vector::new(position, angle + f32::consts::PI)
+        }
+        SpawnPattern::Edges => {
+            // Alternating between the left and the right edge, facing the other one
+            let slots = spawn.count.div_ceil(2);
+            let y = (spawn.index / 2) as f32 + 0.5;
+            if spawn.index.is_multiple_of(2) {
+                Vector::new(Point::new(0.0, y / slots as f32 * size.height), 0.0)
+            } else {
+                Vector::new(Point::new(size.width - 1.0, y / slots as f32 * size.height), f32::consts::PI)
+            }
+        }
+    };
+
+    // Check if the newly spawned enemy is inside the player's grace area (which reaches across
+    // the edges of the world), if so, we push its spawn point to the edge of the area
+    if torus::intersect_circle(vector.position, player, grace_area, size) {
+        // Treat the player as the centre of a circle with radius `grace_area`, and calculate the
+        // angle between the player's position and the enemy's
+        let angle = torus::angle_to(player, vector.position, size);
+        // Use that to place the enemy on the edge of the circle surrounding the player
+        vector.position = torus::normalize(player + Point::from_angle(angle) * grace_area, size);
+    }
+
+    vector
+}
+
+// Returns the velocity of the player during the last update, in pixels per second
+fn player_velocity(player: &Player, dt: f32, size: Size) -> Point {
+    if dt == 0.0 {
+        return Point::new(0.0, 0.0);
+    }
+
+    // Moving across an edge of the world looks like a jump to the other side, unless we take the
+    // shortest way
+    torus::displacement(player.previous_position(), player.position(), size) / dt
+}
+
+#[test]
+fn test_same_seed_same_game() {
+    use rand::SeedableRng;
+    use crate::{CollisionsController, GameRng};
+
+    // Plays ten seconds of a game and returns the positions of everything in the world
+    fn play(seed: u64) -> Vec<Point> {
+        let mut rng = GameRng::seed_from_u64(seed);
+        let mut state = GameState::new(crate::geometry::Size::new(1024.0, 576.0), &mut rng);
+        let mut time_controller = TimeController::new(&state.config);
+        let mut events = Vec::new();
+        state.reset(&mut rng);
+
+        let actions = Actions { rotation: -1.0, shoot: true, ..Actions::default() };
+        for _ in 0..10 * TICKS_PER_SECOND {
+            time_controller.update_seconds(TIMESTEP, &actions, &mut state, &mut events, &mut rng);
+            CollisionsController::handle_collisions(&mut state, &mut time_controller, &mut events);
+        }
+
+        let world = &state.world;
+        let mut positions = vec![world.player.position()];
+        positions.extend(world.enemies.iter().map(|e| e.position()));
+        positions.extend(world.powerups.iter().map(|p| p.position()));
+        positions.extend(world.stars.iter().map(|s| s.position()));
+        positions
+    }
+
+    assert!(play(42) == play(42));
+    assert!(play(42) != play(43));
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{game_state::GameState, models::PowerupKind};
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum Timeout {
+    /// Ends the effect of the given powerup, unless it was collected again in the meantime
+    RemovePowerup(PowerupKind),
+    /// Brings the destroyed player back, at the spot furthest away from the enemies
+    RespawnPlayer,
+    ShowGameOverScreen
+}
+
+impl Timeout {
+    pub fn handle(self, state: &mut GameState, current_time: Duration) {
+        match self {
+            Timeout::RemovePowerup(kind) => {
+                state.world.player.expire_powerup(kind, current_time);
+            }
+            Timeout::RespawnPlayer => {
+                let position = state.world.safest_position();
+                state.world.player.respawn(position, state.config.lives.invulnerability);
+            }
+            Timeout::ShowGameOverScreen => {
+                state.game_over();
+            }
+        }
+    }
+}
+
+#[test]
+fn test_powerups_expire_independently() {
+    use rand::SeedableRng;
+    use crate::{geometry::Size, GameRng};
+
+    let mut rng = GameRng::seed_from_u64(0);
+    let mut state = GameState::new(Size::new(800.0, 600.0), &mut rng);
+    let seconds = Duration::from_secs;
+    let player = &mut state.world.player;
+    player.activate_powerup(PowerupKind::Shield, seconds(0), seconds(10));
+    player.activate_powerup(PowerupKind::TimeSlow, seconds(5), seconds(10));
+
+    // The first shield's timeout doesn't remove the time slow
+    Timeout::RemovePowerup(PowerupKind::Shield).handle(&mut state, seconds(10));
+    assert!(!state.world.player.has_powerup(PowerupKind::Shield));
+    assert!(state.world.player.has_powerup(PowerupKind::TimeSlow));
+
+    // Nor does it remove a shield that was collected again in the meantime
+    state.world.player.activate_powerup(PowerupKind::Shield, seconds(12), seconds(10));
+    Timeout::RemovePowerup(PowerupKind::TimeSlow).handle(&mut state, seconds(15));
+    Timeout::RemovePowerup(PowerupKind::Shield).handle(&mut state, seconds(15));
+    assert!(state.world.player.has_powerup(PowerupKind::Shield));
+    assert!(!state.world.player.has_powerup(PowerupKind::TimeSlow));
+}
@@ -3,6 +3,8 @@ use std::time::Duration;
 use std::cmp::Ordering;
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use crate::controllers::time::Timeout;
 
 // This is a Priority Queue. Rust's native implementation of a BinaryHeap is a max-heap - we want to
@@ -10,6 +12,7 @@ use crate::controllers::time::Timeout;
 // (Duration, Timeout)
 
 /// A ScheduledTimeout is a tuple of a `Duration` (time) and `Timeout` (handler)
+#[derive(Serialize, Deserialize)]
 struct ScheduledTimeout(Duration, Timeout);
 
 // Implement the Debug trait so we can log it
@@ -31,24 +34,21 @@ impl PartialEq for ScheduledTimeout {
 
 impl PartialOrd for ScheduledTimeout {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        other.0.partial_cmp(&self.0)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for ScheduledTimeout {
     fn cmp(&self, other: &ScheduledTimeout) -> Ordering {
-        let ord = self.partial_cmp(other).unwrap();
-        match ord {
-            Ordering::Greater => Ordering::Less,
-            Ordering::Less => Ordering::Greater,
-            Ordering::Equal => ord,
-        }
+        // Reversed, so the earliest timeout ends up at the top of the heap
+        other.0.cmp(&self.0)
     }
 }
 
 /// Our TimeoutQueue. In reality it's just a thin wrapper around a BinaryHeap, but we wrap it so
 /// we can have a min-heap without having to worry about the ScheduledTimeout structure elsewhere in
 /// our code
+#[derive(Serialize, Deserialize)]
 pub struct TimeoutQueue {
     heap: BinaryHeap<ScheduledTimeout>,
 }
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// A `Timer` is used to trigger events in specified intervals
+///
+/// Each time the `update` function is called, the timer will check how many
+/// intervals have elapsed since it was last triggered, and trigger the provided
+/// action once for each of them. If no interval has elapsed, nothing happens.
+#[derive(Serialize, Deserialize)]
+pub struct Timer {
+    last_triggered: Duration,
+    interval: Duration,
+}
+
+impl Timer {
+    pub fn new(interval: Duration) -> Timer {
+        assert!(interval > Duration::from_secs(0), "a timer's interval must be positive");
+        Timer {
+            last_triggered: Duration::from_secs(0),
+            interval,
+        }
+    }
+
+    pub fn from_seconds(seconds: f32) -> Timer {
+        Timer::new(Duration::from_secs_f32(seconds))
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn update<F>(&mut self, current_time: Duration, mut action: F)
+    where
+        F: FnMut(),
+    {
+        while current_time - self.last_triggered >= self.interval {
+            self.last_triggered += self.interval;
+            action();
+        }
+    }
+
+    /// Returns an error if the timer can't have been created with `interval` and updated up to
+    /// `current_time` (e.g. because it comes from a corrupt saved game)
+    pub fn validate(&self, interval: Duration, current_time: Duration) -> Result<(), String> {
+        if self.interval != interval {
+            Err(format!("the interval must be {:?}, but it is {:?}", interval, self.interval))
+        } else if self.last_triggered > current_time {
+            Err(format!("it was last triggered at {:?}, after the current time", self.last_triggered))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Lets time pass without triggering the action
+    ///
+    /// Call this when the timer is not being updated (e.g. the player isn't shooting), so the
+    /// next `update` triggers the action at most once instead of catching up on every interval
+    /// that was skipped in the meantime
+    pub fn rest(&mut self, current_time: Duration) {
+        if current_time - self.last_triggered > self.interval {
+            self.last_triggered = current_time - self.interval;
+        }
+    }
+}
+
+#[test]
+fn test_timer_catches_up() {
+    let mut timer = Timer::from_seconds(0.1);
+    let mut triggered = 0;
+    timer.update(Duration::from_millis(350), || triggered += 1);
+    assert_eq!(triggered, 3);
+
+    timer.rest(Duration::from_secs(10));
+    timer.update(Duration::from_millis(10_050), || triggered += 1);
+    assert_eq!(triggered, 4);
+}
@@ -0,0 +1,384 @@
+//! A gym-style environment, to train agents that play the game
+//!
+//! An `Env` wraps a game and advances it one step at a time: the agent chooses some `Actions`,
+//! the game runs for a few updates, and the agent gets back an `Observation` of the new state,
+//! a reward and whether the game is over.
+
+use std::time::Duration;
+
+use rand::SeedableRng;
+
+use crate::{
+    config::GameConfig,
+    controllers::{Actions, CollisionsController, Event, TimeController, TIMESTEP},
+    game_state::GameState,
+    geometry::{torus, Advance, Point, Position, Size},
+    models::PowerupKind,
+    GameRng,
+};
+
+/// The way the game is presented to the agent
+#[derive(Clone, Copy, Debug)]
+pub enum ObservationKind {
+    /// The player, and the given amount of enemies and powerups nearest to the player
+    Entities { enemies: usize, powerups: usize },
+    /// A downsampled occupancy grid of the world, with the given amount of cells
+    Grid { columns: usize, rows: usize },
+}
+
+/// The configuration of an `Env`
+#[derive(Clone, Copy, Debug)]
+pub struct EnvConfig {
+    /// The size of the world
+    pub size: Size,
+    /// The tuning of the game
+    pub game: GameConfig,
+    /// The kind of observations returned by the environment
+    pub observation: ObservationKind,
+    /// The amount of updates that each step lasts, using the same actions
+    pub ticks_per_step: u32,
+    /// The reward given each time the player is destroyed (usually negative)
+    pub death_reward: f32,
+    /// The game is considered done after this much time, even if the player is still alive
+    pub max_duration: Option<Duration>,
+}
+
+impl Default for EnvConfig {
+    fn default() -> EnvConfig {
+        EnvConfig {
+            size: Size::new(1024.0, 576.0),
+            game: GameConfig::default(),
+            observation: ObservationKind::Entities { enemies: 8, powerups: 2 },
+            ticks_per_step: 4,
+            death_reward: -100.0,
+            max_duration: None,
+        }
+    }
+}
+
+/// A powerup as seen by the agent
+#[derive(Clone, Copy, Debug)]
+pub struct PowerupObservation {
+    /// The offset from the player to the powerup, the shortest way around the edges of the world
+    pub offset: Point,
+    pub kind: PowerupKind,
+}
+
+/// What the agent gets to see of the game
+#[derive(Clone, Debug)]
+pub enum Observation {
+    Entities {
+        /// The position of the player
+        position: Point,
+        /// The direction of the player, in radians
+        direction: f32,
+        /// The temperature of the player's gun, between 0 and 1
+        gun_temperature: f32,
+        /// The powerups in effect
+        active_powerups: Vec<PowerupKind>,
+        /// The offsets from the player to the nearest enemies, nearest first (the shortest way
+        /// around the edges of the world)
+        enemies: Vec<Point>,
+        /// The nearest powerups, nearest first
+        powerups: Vec<PowerupObservation>,
+    },
+    Grid {
+        columns: usize,
+        rows: usize,
+        /// Three layers of `columns * rows` cells, row by row: the player, the enemies and the
+        /// powerups. A cell is 1.0 if it contains something, and 0.0 otherwise
+        cells: Vec<f32>,
+    },
+}
+
+impl Observation {
+    /// Flattens the observation into a vector of numbers, which is what most learning
+    /// algorithms expect
+    ///
+    /// For entity observations, the world is scaled to fit between 0 and 1, and missing enemies
+    /// and powerups are filled with zeros (including their "present" flag), so the length of the
+    /// vector is always the same for a given `ObservationKind`
+    pub fn to_vec(&self, config: &EnvConfig) -> Vec<f32> {
+        match *self {
+            Observation::Entities {
+                position,
+                direction,
+                gun_temperature,
+                ref active_powerups,
+                ref enemies,
+                ref powerups,
+            } => {
+                let (max_enemies, max_powerups) = match config.observation {
+                    ObservationKind::Entities { enemies, powerups } => (enemies, powerups),
+                    ObservationKind::Grid { .. } => (enemies.len(), powerups.len()),
+                };
+                let Size { width, height } = config.size;
+
+                let mut features = vec![
+                    position.x / width,
+                    position.y / height,
+                    direction.cos(),
+                    direction.sin(),
+                    gun_temperature,
+                ];
+                features.extend(PowerupKind::ALL.iter().map(|&kind| one_hot(active_powerups.contains(&kind))));
+
+                for i in 0..max_enemies {
+                    match enemies.get(i) {
+                        Some(offset) => features.extend(&[1.0, offset.x / width, offset.y / height]),
+                        None => features.extend(&[0.0, 0.0, 0.0]),
+                    }
+                }
+
+                for i in 0..max_powerups {
+                    match powerups.get(i) {
+                        Some(p) => {
+                            features.extend(&[1.0, p.offset.x / width, p.offset.y / height]);
+                            features.extend(PowerupKind::ALL.iter().map(|&kind| one_hot(p.kind == kind)));
+                        }
+                        None => features.extend(vec![0.0; 3 + PowerupKind::ALL.len()]),
+                    }
+                }
+
+                features
+            }
+            Observation::Grid { ref cells, .. } => cells.clone(),
+        }
+    }
+}
+
+fn one_hot(condition: bool) -> f32 {
+    if condition { 1.0 } else { 0.0 }
+}
+
+/// A game that is advanced step by step by an agent
+pub struct Env {
+    config: EnvConfig,
+    state: GameState,
+    time_controller: TimeController,
+    rng: GameRng,
+    events: Vec<Event>,
+}
+
+impl EnvConfig {
+    /// Returns an error if the configuration can't be used to create an environment
+    pub fn validate(&self) -> Result<(), String> {
+        if let ObservationKind::Grid { columns, rows } = self.observation {
+            if columns == 0 || rows == 0 {
+                return Err(format!("a grid observation needs at least one cell, not {}x{}", columns, rows));
+            }
+        }
+        if self.ticks_per_step == 0 {
+            return Err("each step must last at least one update".to_string());
+        }
+        self.game.validate()
+    }
+}
+
+impl Env {
+    /// Returns a new environment, with a game started from seed 0, or an error if the
+    /// configuration is invalid
+    pub fn new(config: EnvConfig) -> Result<Env, String> {
+        config.validate()?;
+        let mut rng = GameRng::seed_from_u64(0);
+        let mut state = GameState::with_config(config.size, config.game, &mut rng);
+        state.reset(&mut rng);
+        Ok(Env {
+            config,
+            state,
+            time_controller: TimeController::new(&config.game),
+            rng,
+            events: Vec::new(),
+        })
+    }
+
+    /// Returns the configuration of the environment
+    pub fn config(&self) -> &EnvConfig {
+        &self.config
+    }
+
+    /// Returns the state of the current game
+    pub fn state(&self) -> &GameState {
+        &self.state
+    }
+
+    /// Starts a new game with the given seed, and returns the first observation
+    pub fn reset(&mut self, seed: u64) -> Observation {
+        self.rng = GameRng::seed_from_u64(seed);
+        self.state.reset(&mut self.rng);
+        self.time_controller.reset(&self.state.config);
+        self.events.clear();
+        self.observe()
+    }
+
+    /// Runs the game for `ticks_per_step` updates with the given actions
+    ///
+    /// Returns the new observation, the reward (the increase of the score, plus `death_reward` for
+    /// every time the player was destroyed) and whether the game is over
+    pub fn step(&mut self, actions: Actions) -> (Observation, f32, bool) {
+        let old_score = self.state.score;
+
+        for _ in 0..self.config.ticks_per_step {
+            if self.is_done() {
+                break;
+            }
+
+            self.time_controller.update_seconds(
+                TIMESTEP,
+                &actions,
+                &mut self.state,
+                &mut self.events,
+                &mut self.rng,
+            );
+            CollisionsController::handle_collisions(&mut self.state, &mut self.time_controller, &mut self.events);
+        }
+        // The player can die more than once per step, respawning in between
+        let deaths = self.events.iter().filter(|&&event| event == Event::PlayerDestroyed).count();
+        self.events.clear();
+
+        let reward = (self.state.score - old_score) as f32 + deaths as f32 * self.config.death_reward;
+
+        (self.observe(), reward, self.is_done())
+    }
+
+    /// Returns whether the game is over, either because the player lost their last life or because
+    /// the time ran out
+    pub fn is_done(&self) -> bool {
+        let out_of_time = self
+            .config
+            .max_duration
+            .is_some_and(|max| self.time_controller.current_time() >= max);
+        self.state.is_game_over() || out_of_time
+    }
+
+    /// Returns an observation of the current state of the game
+    pub fn observe(&self) -> Observation {
+        let world = &self.state.world;
+        let player = &world.player;
+
+        match self.config.observation {
+            ObservationKind::Entities { enemies, powerups } => {
+                let origin = player.position();
+                let offset = |p: Point| torus::displacement(origin, p, world.size);
+                let by_distance = |a: &Point, b: &Point| a.squared_length().partial_cmp(&b.squared_length()).unwrap();
+
+                let mut enemy_offsets: Vec<_> = world.enemies.iter().map(|e| offset(e.position())).collect();
+                enemy_offsets.sort_by(by_distance);
+                enemy_offsets.truncate(enemies);
+
+                let mut powerup_observations: Vec<_> = world
+                    .powerups
+                    .iter()
+                    .map(|p| PowerupObservation { offset: offset(p.position()), kind: p.kind })
+                    .collect();
+                powerup_observations.sort_by(|a, b| by_distance(&a.offset, &b.offset));
+                powerup_observations.truncate(powerups);
+
+                Observation::Entities {
+                    position: origin,
+                    direction: player.direction(),
+                    gun_temperature: player.gun.temperature,
+                    active_powerups: player.powerups.iter().map(|p| p.kind).collect(),
+                    enemies: enemy_offsets,
+                    powerups: powerup_observations,
+                }
+            }
+            ObservationKind::Grid { columns, rows } => {
+                let layer = columns * rows;
+                let mut cells = vec![0.0; 3 * layer];
+                let size = world.size;
+                let mut mark = |layer_index: usize, p: Point| {
+                    let column = ((p.x / size.width * columns as f32) as usize).min(columns - 1);
+                    let row = ((p.y / size.height * rows as f32) as usize).min(rows - 1);
+                    cells[layer_index * layer + row * columns + column] = 1.0;
+                };
+
+                if !player.is_dead {
+                    mark(0, player.position());
+                }
+                for enemy in &world.enemies {
+                    mark(1, enemy.position());
+                }
+                for powerup in &world.powerups {
+                    mark(2, powerup.position());
+                }
+
+                Observation::Grid { columns, rows, cells }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_env() {
+    let config = EnvConfig {
+        observation: ObservationKind::Entities { enemies: 4, powerups: 2 },
+        ..EnvConfig::default()
+    };
+    let mut env = Env::new(config).unwrap();
+    let first = env.reset(3).to_vec(&config);
+    assert_eq!(first.len(), 5 + 8 + 4 * 3 + 2 * 11);
+    assert!(first == env.reset(3).to_vec(&config));
+
+    // Doing nothing eventually gets the player killed, once for each life
+    assert_eq!(deaths_reward(&mut env), config.game.lives.starting as f32 * config.death_reward);
+
+    let grid = Env::new(EnvConfig { observation: ObservationKind::Grid { columns: 16, rows: 9 }, ..config }).unwrap();
+    let cells = grid.observe().to_vec(grid.config());
+    assert_eq!(cells.len(), 3 * 16 * 9);
+    assert_eq!(cells.iter().sum::<f32>(), 1.0);
+
+    // A grid without cells has nowhere to put the entities
+    assert!(Env::new(EnvConfig { observation: ObservationKind::Grid { columns: 0, rows: 9 }, ..config }).is_err());
+    assert!(Env::new(EnvConfig { observation: ObservationKind::Grid { columns: 16, rows: 0 }, ..config }).is_err());
+}
+
+// Plays a game doing nothing, returning the part of the rewards that doesn't come from the score
+// (which comes from enemies destroyed by powerups that the player happened to pick up)
+#[cfg(test)]
+fn deaths_reward(env: &mut Env) -> f32 {
+    let mut total_reward = 0.0;
+    let mut done = false;
+    while !done {
+        let (_, reward, is_done) = env.step(Actions::default());
+        total_reward += reward;
+        done = is_done;
+    }
+    total_reward - env.state.score as f32
+}
+
+#[test]
+fn test_every_death_is_penalized() {
+    // Respawning right away, a death and the respawn happen within the same step
+    let mut config = EnvConfig::default();
+    config.game.lives.respawn_delay = 0.0;
+    config.game.lives.invulnerability = 0.0;
+    let mut env = Env::new(config).unwrap();
+    assert_eq!(deaths_reward(&mut env), config.game.lives.starting as f32 * config.death_reward);
+
+    // Long steps cover several deaths each
+    let config = EnvConfig { ticks_per_step: 10_000, ..EnvConfig::default() };
+    let mut env = Env::new(config).unwrap();
+    assert_eq!(deaths_reward(&mut env), config.game.lives.starting as f32 * config.death_reward);
+}
+
+#[test]
+fn test_observation_wraps_around() {
+    use crate::{geometry::Vector, models::{Enemy, EnemyKind}};
+
+    let config = EnvConfig { observation: ObservationKind::Entities { enemies: 2, powerups: 0 }, ..EnvConfig::default() };
+    let mut env = Env::new(config).unwrap();
+    env.state.world.player.vector.position = Point::new(5.0, 288.0);
+    env.state.world.enemies = vec![
+        Enemy::new(EnemyKind::Chaser, Vector::new(Point::new(300.0, 288.0), 0.0)),
+        Enemy::new(EnemyKind::Chaser, Vector::new(Point::new(1019.0, 288.0), 0.0)),
+    ];
+
+    // The enemy near the right edge is only 10 pixels away, across the left edge
+    match env.observe() {
+        Observation::Entities { enemies, .. } => {
+            assert_eq!(enemies, vec![Point::new(-10.0, 0.0), Point::new(295.0, 0.0)]);
+        }
+        Observation::Grid { .. } => unreachable!(),
+    }
+}
@@ -0,0 +1,142 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::GameConfig,
+    geometry::Size,
+    models::World,
+    waves::WaveDirector,
+};
+
+/// This is a message that will be drawn to the screen. When it's shown on the screen the game
+/// will be waiting for user input
+pub struct Message {
+    pub title: &'static str,
+    pub subtitle: &'static str,
+}
+
+/// The Message to show when the game starts
+const WELCOME_MESSAGE: Message = Message {
+    title: "Welcome to Rocket!",
+    subtitle: "Press any key to start",
+};
+
+/// The Message to show when the game is over
+const GAMEOVER_MESSAGE: Message = Message {
+    title: "Game Over",
+    subtitle: "Press any key to restart",
+};
+
+/// The data structure that contains the state of the game
+#[derive(Serialize, Deserialize)]
+pub struct GameState {
+    /// The tuning of the game
+    pub config: GameConfig,
+    /// The world contains everything that needs to be drawn
+    pub world: World,
+    /// The current difficulty - the enemies will speed up with each wave
+    pub difficulty: f32,
+    /// Decides when and where enemies spawn
+    pub waves: WaveDirector,
+    /// Information about the Message to draw on the screen
+    ///
+    /// Messages are not saved: only games in progress are saved, and those don't show a message
+    #[serde(skip)]
+    pub message: Option<Message>,
+    /// The current score of the player
+    pub score: u32,
+    /// The lives left, including the rocket in play, the game is over when they run out
+    pub lives: u32,
+    /// The amount of enemies destroyed in the current game
+    pub enemies_killed: u32,
+    /// The amount of powerups picked up in the current game
+    pub powerups_collected: u32,
+}
+
+impl GameState {
+    /// Returns a new `GameState` containing a `World` of the given `Size`, using the default config
+    pub fn new(size: Size, rng: &mut impl Rng) -> GameState {
+        GameState::with_config(size, GameConfig::default(), rng)
+    }
+
+    /// Returns a new `GameState` containing a `World` of the given `Size`, tuned by `config`
+    pub fn with_config(size: Size, config: GameConfig, rng: &mut impl Rng) -> GameState {
+        GameState {
+            config,
+            world: World::new(rng, size),
+            difficulty: 0.0,
+            waves: WaveDirector::default(),
+            message: Some(WELCOME_MESSAGE),
+            score: 0,
+            lives: config.lives.starting,
+            enemies_killed: 0,
+            powerups_collected: 0,
+        }
+    }
+
+    /// Whether the player has lost their last life
+    pub fn is_game_over(&self) -> bool {
+        self.lives == 0
+    }
+
+    /// Adds points to the score, returning the amount of extra lives gained by doing so
+    pub fn add_score(&mut self, points: u32) -> u32 {
+        let old_score = self.score;
+        self.score = self.score.saturating_add(points);
+
+        // Count the multiples of `extra_life_every` that were reached, none if it's 0
+        let every = self.config.lives.extra_life_every;
+        let reached = |score: u32| score.checked_div(every).unwrap_or(0);
+        let extra_lives = reached(self.score) - reached(old_score);
+        self.lives = self.lives.saturating_add(extra_lives);
+        extra_lives
+    }
+
+    /// Called when the game is over - displays a message onscreen
+    pub fn game_over(&mut self) {
+        self.message = Some(GAMEOVER_MESSAGE);
+    }
+
+    /// Reset our game-state
+    ///
+    /// The new game only depends on the state of `rng`, so seeding it with the same value results
+    /// in the same game
+    pub fn reset(&mut self, rng: &mut impl Rng) {
+        // Replace the world (player, stars, enemies, bullets, powerups and particles) by a new one
+        self.world = World::new(rng, self.world.size);
+        self.world.player.is_dead = false;
+
+        // Reset score, lives and statistics
+        self.score = 0;
+        self.lives = self.config.lives.starting;
+        self.enemies_killed = 0;
+        self.powerups_collected = 0;
+
+        // Reset difficulty and start over from the first wave
+        self.difficulty = 0.0;
+        self.waves.restart();
+
+        // Reset message
+        self.message = None;
+    }
+}
+
+#[test]
+fn test_extra_lives() {
+    use rand::SeedableRng;
+    use crate::GameRng;
+
+    let mut rng = GameRng::seed_from_u64(0);
+    let mut state = GameState::new(Size::new(800.0, 600.0), &mut rng);
+    state.config.lives.extra_life_every = 100;
+    state.reset(&mut rng);
+    assert_eq!(state.lives, 3);
+
+    assert_eq!(state.add_score(90), 0);
+    assert_eq!(state.add_score(20), 1);
+    assert_eq!(state.add_score(200), 2);
+    assert_eq!(state.lives, 6);
+
+    state.config.lives.extra_life_every = 0;
+    assert_eq!(state.add_score(1000), 0);
+}
@@ -0,0 +1,110 @@
+//! The table of the best scores achieved on this machine
+
+use std::cmp::Reverse;
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+/// The amount of scores kept in the table
+pub const MAX_HIGH_SCORES: usize = 10;
+
+/// The maximum length of a player's name, in characters
+pub const MAX_NAME_LENGTH: usize = 12;
+
+/// An entry in the high score table
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    /// The date of the game, formatted as YYYY-MM-DD
+    pub date: String,
+    /// The seed of the game, so it can be played again
+    pub seed: u64,
+    /// The amount of seconds that the player stayed alive
+    pub survival_time: f32,
+}
+
+/// The best scores, from best to worst
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Returns an empty table
+    pub fn new() -> HighScores {
+        HighScores::default()
+    }
+
+    /// Returns the entries, from best to worst
+    pub fn entries(&self) -> &[HighScore] {
+        &self.entries
+    }
+
+    /// Returns true if the given score would make it into the table
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.last().is_none_or(|worst| score > worst.score))
+    }
+
+    /// Adds an entry to the table, dropping the worst entry if the table is full
+    ///
+    /// Returns the position of the new entry (starting at 0), or `None` if it didn't qualify
+    pub fn insert(&mut self, mut entry: HighScore) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+
+        entry.name = entry.name.chars().take(MAX_NAME_LENGTH).collect();
+
+        // Among equal scores, the oldest one stays on top
+        let position = self.entries.iter().position(|e| entry.score > e.score).unwrap_or(self.entries.len());
+        self.entries.insert(position, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(position)
+    }
+
+    /// Writes the table as JSON
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(io::Error::from)
+    }
+
+    /// Reads a table written by `write_to`
+    ///
+    /// Since the file may have been edited by hand, the entries are sorted again and the table is
+    /// cut down to `MAX_HIGH_SCORES` entries
+    pub fn read_from<R: Read>(reader: R) -> io::Result<HighScores> {
+        let mut high_scores: HighScores = serde_json::from_reader(reader).map_err(io::Error::from)?;
+        high_scores.entries.sort_by_key(|e| Reverse(e.score));
+        high_scores.entries.truncate(MAX_HIGH_SCORES);
+        Ok(high_scores)
+    }
+}
+
+#[test]
+fn test_high_scores() {
+    let entry = |name: &str, score| HighScore {
+        name: name.to_string(),
+        score,
+        date: "2026-10-18".to_string(),
+        seed: 0,
+        survival_time: 1.0,
+    };
+
+    let mut high_scores = HighScores::new();
+    assert!(!high_scores.qualifies(0));
+    for score in 1..=MAX_HIGH_SCORES as u32 {
+        high_scores.insert(entry("someone", score * 10));
+    }
+    assert!(!high_scores.qualifies(10));
+    assert_eq!(high_scores.insert(entry("a very long name indeed", 55)), Some(5));
+    assert_eq!(high_scores.entries()[5].name, "a very long ");
+    assert_eq!(high_scores.entries().len(), MAX_HIGH_SCORES);
+    assert_eq!(high_scores.entries().last().unwrap().score, 20);
+
+    let mut bytes = Vec::new();
+    high_scores.write_to(&mut bytes).unwrap();
+    assert_eq!(HighScores::read_from(&bytes[..]).unwrap().entries(), high_scores.entries());
+    assert!(HighScores::read_from(&b"{ corrupt"[..]).is_err());
+}
@@ -0,0 +1,34 @@
+//! The game logic of Rocket, without any rendering, audio or input handling
+//!
+//! This crate does not depend on ggez, so the simulation can be driven from anywhere: the `rocket`
+//! binary uses it as the model behind its window, but it can also run headless (e.g. in tests)
+
+extern crate geometry;
+extern crate geometry_derive;
+
+pub mod bots;
+pub mod config;
+pub mod controllers;
+pub mod env;
+pub mod game_state;
+pub mod high_scores;
+pub mod models;
+pub mod replay;
+pub mod save;
+pub mod simulation;
+pub mod spatial_hash;
+pub mod util;
+pub mod waves;
+
+pub use crate::{
+    config::GameConfig,
+    controllers::{Actions, CollisionsController, Event, TimeController},
+    game_state::GameState,
+    replay::Replay,
+};
+
+/// The random number generator used to run the game
+///
+/// Any `rand::Rng` can drive the simulation, but ChaCha is portable: a given seed results in the
+/// same enemies, powerups and stars on every platform, which makes runs reproducible
+pub type GameRng = rand_chacha::ChaCha8Rng;
@@ -0,0 +1,224 @@
+use std::f32;
+
+use geometry::{torus, Advance, Collide, Interpolate, Point, Position, Shape, Size, Vector};
+use geometry_derive::{Advance, Interpolate, Position};
+use serde::{Deserialize, Serialize};
+
+/// The radius of the boss' armored body
+pub const BODY_RADIUS: f32 = 45.0;
+/// The radius of each of the boss' weak points
+pub const WEAK_POINT_RADIUS: f32 = 10.0;
+const WEAK_POINTS: usize = 3;
+// How fast the weak points turn around the body, in radians per second
+const SPIN_SPEED: f32 = 0.8;
+
+// Movement, in pixels per second
+const SPEED: f32 = 40.0;
+const CHARGE_SPEED: f32 = 260.0;
+// The seconds between the start of two charges, and the seconds that a charge lasts
+const CHARGE_INTERVAL: f32 = 3.0;
+const CHARGE_TIME: f32 = 0.8;
+
+// The seconds between two shots of the bullet spiral, which gets faster in the last phase
+const SPIRAL_INTERVAL: f32 = 0.2;
+const FAST_SPIRAL_INTERVAL: f32 = 0.12;
+// The angle that the spiral turns between two shots
+const SPIRAL_STEP: f32 = 0.35;
+// The seconds between two waves of minions
+const MINION_INTERVAL: f32 = 5.0;
+
+/// The phases of a boss fight, which depend on the health the boss has left
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossPhase {
+    /// The boss shoots a spiral of bullets
+    Spirals,
+    /// Minions join the spiral
+    Minions,
+    /// The boss charges at the player, shooting a faster spiral (and still sending minions)
+    Charges,
+}
+
+/// The part of the boss that something collides with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BossPart {
+    /// The armored body, which bullets can't damage
+    Body,
+    /// One of the weak points, which take damage from bullets
+    WeakPoint,
+}
+
+/// Something the boss does to the player
+#[derive(Clone, Debug)]
+pub enum BossAttack {
+    /// Shoot a bullet with the given vector
+    Shoot(Vector),
+    /// Spawn a minion with the given vector
+    Spawn(Vector),
+}
+
+/// A huge enemy that shows up after some waves
+///
+/// Unlike other enemies its shape is not a single circle: only the weak points around its body
+/// can be damaged, while touching any part of it destroys the player
+#[derive(Advance, Position, Interpolate, Serialize, Deserialize)]
+pub struct Boss {
+    vector: Vector,
+    previous_position: Point,
+    pub health: u32,
+    pub max_health: u32,
+    /// The seconds since the boss appeared
+    age: f32,
+    /// The seconds left before the next shot of the spiral, and the direction of that shot
+    spiral_timer: f32,
+    spiral_angle: f32,
+    /// The seconds left before the next wave of minions
+    minion_timer: f32,
+}
+
+impl Boss {
+    /// Create a boss with the given vector and health
+    pub fn new(vector: Vector, health: u32) -> Boss {
+        Boss {
+            previous_position: vector.position,
+            vector,
+            health,
+            max_health: health,
+            age: 0.0,
+            spiral_timer: SPIRAL_INTERVAL,
+            spiral_angle: 0.0,
+            minion_timer: MINION_INTERVAL,
+        }
+    }
+
+    /// Returns the current phase of the fight
+    pub fn phase(&self) -> BossPhase {
+        let health = self.health as f32 / self.max_health as f32;
+        if health > 2.0 / 3.0 {
+            BossPhase::Spirals
+        } else if health > 1.0 / 3.0 {
+            BossPhase::Minions
+        } else {
+            BossPhase::Charges
+        }
+    }
+
+    /// Returns the positions of the weak points, which turn around the edge of the body
+    pub fn weak_points(&self) -> [Point; WEAK_POINTS] {
+        let mut points = [self.position(); WEAK_POINTS];
+        for (i, point) in points.iter_mut().enumerate() {
+            let angle = self.age * SPIN_SPEED + 2.0 * f32::consts::PI * i as f32 / WEAK_POINTS as f32;
+            *point = *point + Point::from_angle(angle) * BODY_RADIUS;
+        }
+        points
+    }
+
+    /// Returns the part of the boss that collides with `other`, if any (weak points first, since
+    /// they overlap the body), in a world of the given size
+    pub fn part_hit_by<O: Collide>(&self, other: &O, size: Size) -> Option<BossPart> {
+        let shape = other.shape();
+        self.part_touching(|center, radius| {
+            let offset = torus::displacement(center, other.position(), size);
+            Shape::circle(radius).intersects(&shape.translate(offset))
+        })
+    }
+
+    /// Returns the part of the boss that a circle with the given radius touched while moving from
+    /// `start` to `end` (the shortest way around a world of the given size), see `part_hit_by`
+    pub fn part_swept_by(&self, start: Point, end: Point, radius: f32, size: Size) -> Option<BossPart> {
+        self.part_touching(|center, part_radius| {
+            torus::intersect_segment_circle(start, end, center, part_radius + radius, size)
+        })
+    }
+
+    // Returns the first part for which `touches` is true, given the center and radius of each part
+    fn part_touching(&self, touches: impl Fn(Point, f32) -> bool) -> Option<BossPart> {
+        if self.weak_points().iter().any(|&point| touches(point, WEAK_POINT_RADIUS)) {
+            Some(BossPart::WeakPoint)
+        } else if touches(self.position(), BODY_RADIUS) {
+            Some(BossPart::Body)
+        } else {
+            None
+        }
+    }
+
+    /// Damages a weak point, returning true if that destroys the boss
+    pub fn hit(&mut self) -> bool {
+        self.health = self.health.saturating_sub(1);
+        self.health == 0
+    }
+
+    /// Moves the boss, and returns its attacks
+    ///
+    /// The boss slowly follows the player, except when charging at them in the last phase
+    pub fn update(&mut self, dt: f32, player_position: Point, size: Size) -> Vec<BossAttack> {
+        self.age += dt;
+        let phase = self.phase();
+        let mut attacks = Vec::new();
+
+        let target = torus::nearest_copy(self.vector.position, player_position, size);
+        let charging = phase == BossPhase::Charges && self.age % CHARGE_INTERVAL < CHARGE_TIME;
+        if charging {
+            self.advance_wrapping(dt * CHARGE_SPEED, size);
+        } else {
+            self.point_to(target);
+            self.advance_wrapping(dt * SPEED, size);
+        }
+
+        // Two bullets in opposite directions, turning a bit with each shot
+        self.spiral_timer -= dt;
+        if self.spiral_timer <= 0.0 {
+            self.spiral_timer += if phase == BossPhase::Charges { FAST_SPIRAL_INTERVAL } else { SPIRAL_INTERVAL };
+            self.spiral_angle += SPIRAL_STEP;
+            for &angle in &[self.spiral_angle, self.spiral_angle + f32::consts::PI] {
+                let edge = self.position() + Point::from_angle(angle) * BODY_RADIUS;
+                attacks.push(BossAttack::Shoot(Vector::new(edge, angle)));
+            }
+        }
+
+        // A minion from each weak point, heading away from the body
+        if phase != BossPhase::Spirals {
+            self.minion_timer -= dt;
+            if self.minion_timer <= 0.0 {
+                self.minion_timer += MINION_INTERVAL;
+                for point in &self.weak_points() {
+                    let mut vector = Vector::new(*point, 0.0);
+                    vector.point_to(*point + (*point - self.position()));
+                    attacks.push(BossAttack::Spawn(vector));
+                }
+            }
+        }
+
+        attacks
+    }
+}
+
+#[test]
+fn test_boss() {
+    use crate::models::Bullet;
+
+    let size = Size::new(1000.0, 1000.0);
+    let mut boss = Boss::new(Vector::new(Point::new(500.0, 500.0), 0.0), 6);
+    assert_eq!(boss.phase(), BossPhase::Spirals);
+
+    // The weak points take damage, the body doesn't
+    let weak_point = boss.weak_points()[0];
+    let bullet = |position| Bullet::new(Vector::new(position, 0.0), 100.0);
+    assert_eq!(boss.part_hit_by(&bullet(weak_point), size), Some(BossPart::WeakPoint));
+    assert_eq!(boss.part_hit_by(&bullet(boss.position()), size), Some(BossPart::Body));
+    assert_eq!(boss.part_hit_by(&bullet(Point::new(100.0, 100.0)), size), None);
+
+    // A fast bullet can fly over a weak point within a single update
+    let before = weak_point + Point::new(-30.0, 0.0);
+    let after = weak_point + Point::new(30.0, 0.0);
+    assert_eq!(boss.part_swept_by(before, after, 3.0, size), Some(BossPart::WeakPoint));
+    assert_eq!(boss.part_swept_by(Point::new(100.0, 100.0), Point::new(100.0, 900.0), 3.0, size), None);
+
+    // Losing health moves the fight to the next phases, which bring minions
+    assert!(!boss.hit() && !boss.hit() && !boss.hit());
+    assert_eq!(boss.phase(), BossPhase::Minions);
+    let attacks = boss.update(MINION_INTERVAL, Point::new(100.0, 100.0), size);
+    assert!(attacks.iter().any(|attack| matches!(attack, BossAttack::Spawn(_))));
+    assert!(!boss.hit() && !boss.hit());
+    assert_eq!(boss.phase(), BossPhase::Charges);
+    assert!(boss.hit());
+}
@@ -0,0 +1,115 @@
+use std::f32;
+
+use geometry::{torus, Advance, Collide, Interpolate, Point, Position, Size, Vector};
+use geometry_derive::{Advance, Interpolate, Position};
+use serde::{Deserialize, Serialize};
+
+use crate::util;
+
+/// Bullets are spawned when the player shoots
+///
+/// When an enemy is reached by a bullet, it will explode
+#[derive(Clone, Advance, Position, Interpolate, Serialize, Deserialize)]
+pub struct Bullet {
+    vector: Vector,
+    previous_position: Point,
+    distance_left: f32,
+    /// Whether the bullet steers towards its targets (see `Bullet::steer`)
+    pub homing: bool,
+}
+
+impl Bullet {
+    /// Create a bullet with the given vector, which disappears after travelling `distance`
+    pub fn new(vector: Vector, distance: f32) -> Bullet {
+        Bullet { previous_position: vector.position, vector, distance_left: distance, homing: false }
+    }
+
+    /// Create a homing bullet, see `Bullet::new`
+    pub fn homing(vector: Vector, distance: f32) -> Bullet {
+        Bullet { homing: true, ..Bullet::new(vector, distance) }
+    }
+
+    /// Turns a homing bullet towards the nearest of `targets`, by at most `max_turn` radians
+    ///
+    /// Targets are reached through the shortest way around `size`, and only the ones in front of
+    /// the bullet are considered, so it doesn't circle around targets that it missed
+    pub fn steer(&mut self, targets: impl Iterator<Item = Point>, max_turn: f32, size: Size) {
+        if !self.homing {
+            return;
+        }
+
+        let direction = self.direction();
+        let position = self.position();
+        let nearest = targets
+            .filter_map(|target| {
+                let turn = util::angle_difference(direction, torus::angle_to(position, target, size));
+                (turn.abs() < f32::consts::FRAC_PI_2).then_some((torus::squared_distance(position, target, size), turn))
+            })
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+        if let Some((_, turn)) = nearest {
+            *self.direction_mut() += turn.clamp(-max_turn, max_turn);
+        }
+    }
+
+    /// Update the bullet's position
+    ///
+    /// Its previous position is the one stored at the start of every update (see
+    /// `TimeController::store_positions`), which `Bullet::path` relies on
+    pub fn update(&mut self, units: f32, size: Size) {
+        self.advance_wrapping(units, size);
+        self.distance_left -= units;
+    }
+
+    /// Returns where the bullet started and ended its last update
+    ///
+    /// The start might be outside of the bounds, when the bullet wrapped around the edge of a
+    /// world of the given size, so that the path between them is a straight segment
+    pub fn path(&self, size: Size) -> (Point, Point) {
+        let end = self.position();
+        (end - torus::displacement(self.previous_position(), end, size), end)
+    }
+
+    /// Check if the bullet needs to be destroyed
+    pub fn reached_max_distance(&self) -> bool {
+        self.distance_left <= 0.
+    }
+}
+
+impl Collide for Bullet {
+    fn radius(&self) -> f32 {
+        3.0
+    }
+}
+
+#[test]
+fn test_homing_bullet() {
+    let size = Size::new(1000.0, 1000.0);
+    let target = Point::new(600.0, 600.0);
+
+    // Regular bullets fly straight, homing bullets turn towards the targets in front of them
+    let mut bullet = Bullet::new(Vector::new(Point::new(500.0, 500.0), 0.0), 100.0);
+    bullet.steer(std::iter::once(target), 0.1, size);
+    assert_eq!(bullet.direction(), 0.0);
+
+    let mut bullet = Bullet::homing(Vector::new(Point::new(500.0, 500.0), 0.0), 100.0);
+    bullet.steer(std::iter::once(target), 0.1, size);
+    assert_eq!(bullet.direction(), 0.1);
+    bullet.steer(std::iter::once(Point::new(100.0, 500.0)), 0.1, size);
+    assert_eq!(bullet.direction(), 0.1);
+}
+
+#[test]
+fn test_bullet_path() {
+    let size = Size::new(1000.0, 1000.0);
+
+    // The path of a bullet that wraps around the right edge starts outside of the bounds
+    let mut bullet = Bullet::new(Vector::new(Point::new(990.0, 500.0), 0.0), 100.0);
+    bullet.store_position();
+    bullet.update(20.0, size);
+    assert_eq!(bullet.position(), Point::new(10.0, 500.0));
+    assert_eq!(bullet.path(size), (Point::new(-10.0, 500.0), Point::new(10.0, 500.0)));
+    bullet.store_position();
+    bullet.update(20.0, size);
+    assert_eq!(bullet.path(size), (Point::new(10.0, 500.0), Point::new(30.0, 500.0)));
+}
@@ -0,0 +1,279 @@
+use std::f32;
+
+use rand::{distributions::{Distribution, WeightedIndex}, Rng};
+use geometry::{torus, Point, Size, Advance, Position, Collide, Interpolate, Vector};
+use geometry_derive::{Advance, Interpolate, Position};
+use serde::{Deserialize, Serialize};
+
+// The seconds that a dasher spends aiming at the player, and then charging at them
+const DASHER_AIM_TIME: f32 = 1.4;
+const DASHER_DASH_TIME: f32 = 0.6;
+// The distance at which orbiters circle the player
+const ORBIT_RADIUS: f32 = 150.0;
+// Shooters get this close to the player, and back off when the player gets much closer
+const SHOOTER_RANGE: f32 = 250.0;
+// The seconds between the shots of a shooter
+const SHOOTER_RELOAD_TIME: f32 = 2.0;
+
+/// The different kinds of enemies, which look and behave differently
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub enum EnemyKind {
+    /// Heads straight for the player
+    #[default]
+    Chaser,
+    /// A big and slow chaser, which takes three hits to destroy
+    Tank,
+    /// Aims at the player while slowly closing in, and then charges at them
+    Dasher,
+    /// A chaser that breaks into two fragments when destroyed
+    Splitter,
+    /// A small and fast chaser, left behind by a splitter
+    Fragment,
+    /// Circles around the player, waiting for them to fly into it
+    Orbiter,
+    /// Keeps its distance from the player, and shoots at them
+    Shooter,
+}
+
+impl EnemyKind {
+    /// Returns a random kind of enemy to spawn
+    ///
+    /// At the start of the game only chasers spawn, the other kinds (except fragments, which
+    /// never spawn on their own) become more common as the difficulty rises
+    pub fn random<R: Rng>(rng: &mut R, difficulty: f32) -> EnemyKind {
+        let d = difficulty.clamp(0.0, 2.0);
+        let choices = [
+            (EnemyKind::Chaser, 10.0),
+            (EnemyKind::Tank, 3.0 * d),
+            (EnemyKind::Dasher, 3.0 * d),
+            (EnemyKind::Splitter, 3.0 * d),
+            (EnemyKind::Orbiter, 2.0 * d),
+            (EnemyKind::Shooter, 2.0 * d),
+        ];
+        let weights = WeightedIndex::new(choices.iter().map(|&(_, weight)| weight)).unwrap();
+        choices[weights.sample(rng)].0
+    }
+
+    /// The radius of the enemy's body
+    pub fn radius(self) -> f32 {
+        match self {
+            EnemyKind::Chaser => 10.0,
+            EnemyKind::Tank => 16.0,
+            EnemyKind::Dasher => 9.0,
+            EnemyKind::Splitter => 13.0,
+            EnemyKind::Fragment => 6.0,
+            EnemyKind::Orbiter => 10.0,
+            EnemyKind::Shooter => 11.0,
+        }
+    }
+
+    /// The speed of the enemy, relative to the speed of a chaser
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            EnemyKind::Chaser => 1.0,
+            EnemyKind::Tank => 0.5,
+            // A dasher is slow while aiming, see `Enemy::update`
+            EnemyKind::Dasher => 0.4,
+            EnemyKind::Splitter => 0.8,
+            EnemyKind::Fragment => 1.3,
+            EnemyKind::Orbiter => 1.5,
+            EnemyKind::Shooter => 0.7,
+        }
+    }
+
+    /// The amount of bullets it takes to destroy the enemy
+    pub fn hit_points(self) -> u32 {
+        match self {
+            EnemyKind::Tank => 3,
+            EnemyKind::Shooter => 2,
+            _ => 1,
+        }
+    }
+
+    /// The score gained when destroying the enemy, relative to the score of a chaser
+    pub fn score_factor(self) -> u32 {
+        match self {
+            EnemyKind::Chaser | EnemyKind::Fragment => 1,
+            EnemyKind::Dasher | EnemyKind::Splitter | EnemyKind::Orbiter => 2,
+            EnemyKind::Tank | EnemyKind::Shooter => 3,
+        }
+    }
+}
+
+/// Enemies follow the player in order to cause a collision and let him explode
+#[derive(Advance, Position, Interpolate, Serialize, Deserialize)]
+pub struct Enemy {
+    vector: Vector,
+    previous_position: Point,
+    pub kind: EnemyKind,
+    /// The amount of bullets left to destroy the enemy
+    pub hit_points: u32,
+    /// The seconds since the enemy spawned, used by the kinds whose behaviour changes over time
+    age: f32,
+    /// The seconds left before a shooter can shoot again
+    reload: f32,
+}
+
+impl Enemy {
+    /// Create a enemy of the given kind with the given vector
+    pub fn new(kind: EnemyKind, vector: Vector) -> Enemy {
+        Enemy {
+            previous_position: vector.position,
+            vector,
+            kind,
+            hit_points: kind.hit_points(),
+            age: 0.0,
+            reload: SHOOTER_RELOAD_TIME,
+        }
+    }
+
+    /// Update the enemy
+    ///
+    /// `speed` is the speed of a chaser, in pixels per second, which the enemy adjusts to its kind
+    pub fn update(&mut self, dt: f32, speed: f32, player_position: Point, size: Size) {
+        self.age += dt;
+        let target = torus::nearest_copy(self.vector.position, player_position, size);
+        let mut speed = speed * self.kind.speed_factor();
+
+        match self.kind {
+            EnemyKind::Chaser | EnemyKind::Tank | EnemyKind::Splitter | EnemyKind::Fragment => {
+                self.point_to(target);
+            }
+            EnemyKind::Dasher => {
+                // Keep aiming until the dash starts, then charge without turning
+                if self.age % (DASHER_AIM_TIME + DASHER_DASH_TIME) < DASHER_AIM_TIME {
+                    self.point_to(target);
+                } else {
+                    speed *= 7.5;
+                }
+            }
+            EnemyKind::Orbiter => {
+                // Head for the player when far away, fly around them at the orbit radius and
+                // move away when closer than that
+                let delta = target - self.vector.position;
+                let distance = delta.length();
+                if distance > 0.0 {
+                    let turn = f32::consts::FRAC_PI_2 * (ORBIT_RADIUS / distance).min(2.0);
+                    *self.direction_mut() = delta.angle() + turn;
+                }
+            }
+            EnemyKind::Shooter => {
+                // Face the player, closing in until in range and backing off when too close
+                self.point_to(target);
+                let distance = self.vector.position.squared_distance_to(target).sqrt();
+                if distance < 0.6 * SHOOTER_RANGE {
+                    speed = -speed;
+                } else if distance < SHOOTER_RANGE {
+                    speed = 0.0;
+                }
+            }
+        }
+
+        self.advance_wrapping(dt * speed, size);
+    }
+
+    /// Returns the vector of a bullet fired at the player, if this is a shooter that has reloaded
+    ///
+    /// The shooter aims at where the player will be when the bullet gets there, assuming the
+    /// player keeps moving at `player_velocity`. Velocities are in pixels per second.
+    pub fn shoot(
+        &mut self,
+        dt: f32,
+        player_position: Point,
+        player_velocity: Point,
+        bullet_speed: f32,
+        size: Size,
+    ) -> Option<Vector> {
+        if self.kind != EnemyKind::Shooter {
+            return None;
+        }
+
+        self.reload -= dt;
+        if self.reload > 0.0 {
+            return None;
+        }
+        self.reload += SHOOTER_RELOAD_TIME;
+
+        // Refine the time it takes the bullet to reach the player a few times, since where the
+        // player will be depends on it
+        let origin = self.vector.position;
+        let target = torus::nearest_copy(origin, player_position, size);
+        let mut predicted = target;
+        for _ in 0..3 {
+            let time = origin.squared_distance_to(predicted).sqrt() / bullet_speed;
+            predicted = target + player_velocity * time;
+        }
+
+        let mut aim = Vector::new(origin, 0.0);
+        aim.point_to(predicted);
+        Some(aim)
+    }
+
+    /// Lets a bullet hit the enemy, returning true if it's destroyed
+    pub fn hit(&mut self) -> bool {
+        self.hit_points = self.hit_points.saturating_sub(1);
+        self.hit_points == 0
+    }
+
+    /// Returns the enemies that are left behind when this enemy is destroyed
+    pub fn fragments(&self) -> Vec<Enemy> {
+        match self.kind {
+            EnemyKind::Splitter => {
+                let position = self.vector.position;
+                let direction = self.direction();
+                vec![
+                    Enemy::new(EnemyKind::Fragment, Vector::new(position, direction - f32::consts::FRAC_PI_2)),
+                    Enemy::new(EnemyKind::Fragment, Vector::new(position, direction + f32::consts::FRAC_PI_2)),
+                ]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Collide for Enemy {
+    fn radius(&self) -> f32 {
+        self.kind.radius()
+    }
+}
+
+#[test]
+fn test_enemy_kinds() {
+    let size = Size::new(1000.0, 1000.0);
+    let player = Point::new(500.0, 500.0);
+
+    // A tank takes three hits
+    let mut tank = Enemy::new(EnemyKind::Tank, Vector::new(Point::new(100.0, 500.0), 0.0));
+    assert!(!tank.hit() && !tank.hit() && tank.hit());
+
+    // A splitter leaves two fragments behind, the other kinds nothing
+    let splitter = Enemy::new(EnemyKind::Splitter, Vector::new(Point::new(100.0, 500.0), 0.0));
+    assert_eq!(splitter.fragments().len(), 2);
+    assert!(tank.fragments().is_empty());
+
+    // An orbiter at the orbit radius flies around the player instead of towards them
+    let mut orbiter = Enemy::new(EnemyKind::Orbiter, Vector::new(Point::new(500.0 - ORBIT_RADIUS, 500.0), 0.0));
+    orbiter.update(0.1, 100.0, player, size);
+    let distance = orbiter.position().squared_distance_to(player).sqrt();
+    assert!(distance >= ORBIT_RADIUS);
+
+    // A dasher charges after aiming
+    let mut dasher = Enemy::new(EnemyKind::Dasher, Vector::new(Point::new(100.0, 500.0), 0.0));
+    let mut step = |dt| {
+        let x = dasher.position().x;
+        dasher.update(dt, 100.0, player, size);
+        dasher.position().x - x
+    };
+    let aiming = step(0.1);
+    step(DASHER_AIM_TIME);
+    let dashing = step(0.1);
+    assert!(dashing > 5.0 * aiming);
+
+    // A shooter leads its target, and waits before shooting again
+    let mut shooter = Enemy::new(EnemyKind::Shooter, Vector::new(Point::new(500.0, 200.0), 0.0));
+    let player_velocity = Point::new(100.0, 0.0);
+    assert!(shooter.shoot(1.0, player, player_velocity, 300.0, size).is_none());
+    let aim = shooter.shoot(1.0, player, player_velocity, 300.0, size).unwrap();
+    assert!(aim.direction > 0.0 && aim.direction < f32::consts::FRAC_PI_2);
+    assert!(shooter.shoot(1.0, player, player_velocity, 300.0, size).is_none());
+}
@@ -0,0 +1,38 @@
+use geometry::{Advance, Collide, Interpolate, Point, Position, Size, Vector};
+use geometry_derive::{Advance, Interpolate, Position};
+use serde::{Deserialize, Serialize};
+
+/// Enemy bullets are spawned when a shooter fires at the player
+///
+/// When the player is reached by an enemy bullet, it will explode (unless it has a shield). The
+/// player can also shoot enemy bullets down.
+#[derive(Clone, Advance, Position, Interpolate, Serialize, Deserialize)]
+pub struct EnemyBullet {
+    vector: Vector,
+    previous_position: Point,
+    distance_left: f32,
+}
+
+impl EnemyBullet {
+    /// Create an enemy bullet with the given vector, which disappears after travelling `distance`
+    pub fn new(vector: Vector, distance: f32) -> EnemyBullet {
+        EnemyBullet { previous_position: vector.position, vector, distance_left: distance }
+    }
+
+    /// Update the bullet's position
+    pub fn update(&mut self, units: f32, size: Size) {
+        self.advance_wrapping(units, size);
+        self.distance_left -= units;
+    }
+
+    /// Check if the bullet needs to be destroyed
+    pub fn reached_max_distance(&self) -> bool {
+        self.distance_left <= 0.
+    }
+}
+
+impl Collide for EnemyBullet {
+    fn radius(&self) -> f32 {
+        4.0
+    }
+}
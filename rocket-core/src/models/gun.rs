@@ -1,10 +1,10 @@
-const HEAT_PER_SHOT: f32 = 0.025;
-const NATURAL_COOL_DOWN_RATE: f32 = 0.2;
-const OVERHEAT_COOL_DOWN_RATE: f32 = 0.4;
+use serde::{Deserialize, Serialize};
+
+use crate::config::GunConfig;
 
 /// This is the player's gun: it slowly overheats with every shot so that the player can't just
 /// spam shooting and ruin the gameplay
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct Gun {
     /// The current temperature of the gun - a percentage between 0 and 1
     pub temperature: f32,
@@ -18,17 +18,17 @@ impl Gun {
     }
 
     /// This is called every tick and slowly cools the gun down
-    pub fn cool_down(&mut self, dt: f32) {
+    pub fn cool_down(&mut self, dt: f32, config: &GunConfig) {
         if self.overheated {
-            self.overheat_cool_down(dt);
+            self.overheat_cool_down(dt * config.overheat_cool_down_rate);
         } else {
-            self.natural_cool_down(dt);
+            self.natural_cool_down(dt * config.natural_cool_down_rate);
         }
     }
 
     /// Whenever the gun is fired it heats up
-    pub fn heat_up(&mut self) {
-        self.temperature = f32::min(1.0, self.temperature + HEAT_PER_SHOT);
+    pub fn heat_up(&mut self, config: &GunConfig) {
+        self.temperature = f32::min(1.0, self.temperature + config.heat_per_shot);
         if self.temperature == 1.0 {
             self.overheated = true;
         }
@@ -46,13 +46,13 @@ impl Gun {
     }
 
     /// Cool down the gun naturally
-    fn natural_cool_down(&mut self, dt: f32) {
-        self.temperature = f32::max(0.0, self.temperature - NATURAL_COOL_DOWN_RATE * dt);
+    fn natural_cool_down(&mut self, amount: f32) {
+        self.temperature = f32::max(0.0, self.temperature - amount);
     }
 
     /// The gun cools down faster if it has overheated
-    fn overheat_cool_down(&mut self, dt: f32) {
-        self.temperature = f32::max(0.0, self.temperature - OVERHEAT_COOL_DOWN_RATE * dt);
+    fn overheat_cool_down(&mut self, amount: f32) {
+        self.temperature = f32::max(0.0, self.temperature - amount);
         if self.temperature == 0.0 {
             self.overheated = false;
         }
@@ -0,0 +1,21 @@
+mod powerup;
+mod boss;
+mod bullet;
+mod enemy;
+mod enemy_bullet;
+mod particle;
+mod player;
+mod world;
+mod star;
+mod gun;
+
+pub use self::powerup::{ActivePowerup, Powerup, PowerupKind};
+pub use self::boss::{Boss, BossAttack, BossPart, BossPhase, BODY_RADIUS as BOSS_BODY_RADIUS, WEAK_POINT_RADIUS as BOSS_WEAK_POINT_RADIUS};
+pub use self::bullet::Bullet;
+pub use self::enemy::{Enemy, EnemyKind};
+pub use self::enemy_bullet::EnemyBullet;
+pub use self::particle::Particle;
+pub use self::player::{POLYGON as PLAYER_POLYGON, Player};
+pub use self::world::World;
+pub use self::star::Star;
+pub use self::gun::Gun;
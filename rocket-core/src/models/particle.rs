@@ -1,14 +1,16 @@
-use geometry::{Advance, Position, Vector};
-use geometry_derive::{Advance, Position};
+use geometry::{Advance, Interpolate, Point, Position, Vector};
+use geometry_derive::{Advance, Interpolate, Position};
+use serde::{Deserialize, Serialize};
 
 /// A model representing a particle
 ///
 /// Particles are visible objects that have a time to live and move around
 /// in a given direction until their time is up. They are spawned when the
 /// player or an enemy is killed
-#[derive(Advance, Position)]
+#[derive(Advance, Position, Interpolate, Serialize, Deserialize)]
 pub struct Particle {
     pub vector: Vector,
+    previous_position: Point,
     pub ttl: f32,
 }
 
@@ -16,8 +18,9 @@ impl Particle {
     /// Create a particle with the given vector and time to live in seconds
     pub fn new(vector: Vector, ttl: f32) -> Particle {
         Particle {
-            vector: vector,
-            ttl: ttl,
+            previous_position: vector.position,
+            vector,
+            ttl,
         }
     }
 
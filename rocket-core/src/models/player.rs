@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+use super::{ActivePowerup, Gun, PowerupKind};
+use geometry::{Advance, Collide, Interpolate, Point, Position, Shape, Size, Vector};
+use geometry_derive::{Advance, Interpolate, Position};
+use serde::{Deserialize, Serialize};
+
+use crate::util;
+
+/// The `Player` is the rocket controlled by the user
+#[derive(Default, Advance, Position, Interpolate, Serialize, Deserialize)]
+pub struct Player {
+    pub vector: Vector,
+    previous_position: Point,
+    pub is_dead: bool,
+    /// The seconds left during which the rocket can't be destroyed, after respawning
+    pub invulnerability: f32,
+    /// The powerups in effect, at most one of each kind
+    pub powerups: Vec<ActivePowerup>,
+    pub gun: Gun,
+}
+
+/// The player is represented as the polygon below
+pub const POLYGON: &[[f32; 2]] = &[[-10.0, -8.0], [10.0, 0.0], [-10.0, 8.0]];
+
+// The radius of a circle around the polygon, a bit more than the distance to its farthest vertex
+const RADIUS: f32 = 12.9;
+
+impl Player {
+    /// Create a new `Player` with a random position and direction
+    pub fn random<R: Rng>(rng: &mut R, bounds: Size) -> Player {
+        let vector = Vector::random(rng, bounds);
+        Player {
+            previous_position: vector.position,
+            vector,
+            is_dead: true,
+            invulnerability: 0.0,
+            powerups: Vec::new(),
+            gun: Gun::new(),
+        }
+    }
+
+    /// Brings a destroyed rocket back at the given position, unable to be destroyed for
+    /// `invulnerability` seconds
+    ///
+    /// The rocket keeps its direction, but loses its powerups and gets a cold gun
+    pub fn respawn(&mut self, position: Point, invulnerability: f32) {
+        self.vector.position = position;
+        self.previous_position = position;
+        self.is_dead = false;
+        self.invulnerability = invulnerability;
+        self.powerups.clear();
+        self.gun = Gun::new();
+    }
+
+    /// Whether the rocket can't be destroyed, because it has just respawned
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerability > 0.0
+    }
+
+    /// Whether a powerup of the given kind is in effect
+    pub fn has_powerup(&self, kind: PowerupKind) -> bool {
+        self.powerups.iter().any(|p| p.kind == kind)
+    }
+
+    /// Starts the effect of a powerup, which lasts `duration` from `current_time` on
+    ///
+    /// If a powerup of the same kind is already in effect, it's extended instead
+    pub fn activate_powerup(&mut self, kind: PowerupKind, current_time: Duration, duration: Duration) {
+        let powerup = ActivePowerup { kind, expires_at: current_time + duration, duration };
+        match self.powerups.iter_mut().find(|p| p.kind == kind) {
+            Some(active) => *active = powerup,
+            None => self.powerups.push(powerup),
+        }
+    }
+
+    /// Ends the effect of the powerup of the given kind right away
+    pub fn remove_powerup(&mut self, kind: PowerupKind) {
+        self.powerups.retain(|p| p.kind != kind);
+    }
+
+    /// Ends the effect of the powerup of the given kind if it has expired at `current_time`
+    pub fn expire_powerup(&mut self, kind: PowerupKind, current_time: Duration) {
+        self.powerups.retain(|p| p.kind != kind || p.expires_at > current_time);
+    }
+
+    /// Returns the rotation (between -1 and 1, as in `Actions::rotation`) that turns the rocket
+    /// towards `target` as fast as possible, without overshooting it
+    ///
+    /// `max_turn` is the angle that a rotation of 1 turns during an update. The target is aimed at
+    /// through the shortest way around `bounds`, since the world wraps around.
+    pub fn rotation_towards(&self, target: Point, bounds: Size, max_turn: f32) -> f32 {
+        if target == self.position() {
+            return 0.0;
+        }
+
+        let mut aim = self.vector.clone();
+        aim.point_to_wrapping(target, bounds);
+        let delta = util::angle_difference(self.direction(), aim.direction);
+        (delta / max_turn).clamp(-1.0, 1.0)
+    }
+
+    /// Returns the front of the rocket
+    pub fn front(&self) -> Point {
+        Point::new(POLYGON[1][0], POLYGON[1][1])
+            .rotate(self.direction())
+            .translate(&self.position())
+    }
+}
+
+impl Collide for Player {
+    fn radius(&self) -> f32 {
+        RADIUS
+    }
+
+    fn shape(&self) -> Shape {
+        Shape::polygon(POLYGON).rotate(self.direction())
+    }
+}
+
+#[test]
+fn test_rotation_towards() {
+    let bounds = Size::new(1000.0, 1000.0);
+    let player = Player { vector: Vector::new(Point::new(900.0, 500.0), 0.0), ..Player::default() };
+
+    // Slightly to the right of the rocket, so it turns clockwise (but not at full speed)
+    let rotation = player.rotation_towards(Point::new(950.0, 505.0), bounds, 0.5);
+    assert!(rotation > 0.0 && rotation < 1.0);
+
+    // Behind the rocket, but closer across the right edge of the world, so it doesn't turn
+    assert_eq!(player.rotation_towards(Point::new(50.0, 500.0), bounds, 0.5), 0.0);
+
+    // Slightly above the rocket across the edge, so it turns anticlockwise at full speed
+    assert_eq!(player.rotation_towards(Point::new(50.0, 300.0), bounds, 0.1), -1.0);
+}
+
+#[test]
+fn test_player_shape() {
+    let player = Player { vector: Vector::new(Point::new(500.0, 500.0), std::f32::consts::FRAC_PI_2), ..Player::default() };
+    let bullet = |x, y| super::Bullet::new(Vector::new(Point::new(x, y), 0.0), 100.0);
+    assert!(Shape::polygon(POLYGON).bounding_radius() < player.radius());
+
+    // The rocket points down, so its tip is below its position and its sides are far apart above it
+    assert!(player.collides_with(&bullet(500.0, 511.0)));
+    assert!(player.collides_with(&bullet(491.0, 491.0)));
+    assert!(!player.collides_with(&bullet(509.0, 500.0)));
+    assert!(!player.collides_with(&bullet(500.0, 486.0)));
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use rand::{distributions::{Distribution, WeightedIndex}, Rng};
+use geometry::{Collide, Point, Position, Size};
+use serde::{Deserialize, Serialize};
+
+use crate::config::PowerupWeights;
+
+const POWERUP_SIZE: f32 = 20.0;
+
+/// This is an enum of the different powerup types
+#[derive(PartialEq, Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub enum PowerupKind {
+    /// Provides the player with a temporary shield
+    Shield,
+    /// Slows down enemies (as well as stars in backdrop)
+    TimeSlow,
+    /// Shoots three bullets at once in different directions
+    #[default]
+    TripleShot,
+    /// Bullets steer towards the nearest enemy
+    HomingMissiles,
+    /// Cools the gun down, and keeps it from heating up
+    Coolant,
+    /// Destroys every enemy and enemy bullet at once (a boss survives it)
+    SmartBomb,
+    /// Pulls the powerups around the player towards them
+    Magnet,
+    /// Gives the player an extra life
+    ExtraLife,
+}
+
+impl PowerupKind {
+    /// Every kind of powerup
+    pub const ALL: [PowerupKind; 8] = [
+        PowerupKind::Shield,
+        PowerupKind::TimeSlow,
+        PowerupKind::TripleShot,
+        PowerupKind::HomingMissiles,
+        PowerupKind::Coolant,
+        PowerupKind::SmartBomb,
+        PowerupKind::Magnet,
+        PowerupKind::ExtraLife,
+    ];
+
+    /// Returns a random kind of powerup, with the chances given by `weights`
+    pub fn random<R: Rng>(rng: &mut R, weights: &PowerupWeights) -> PowerupKind {
+        let weights = WeightedIndex::new(PowerupKind::ALL.iter().map(|&kind| weights.weight(kind))).unwrap();
+        PowerupKind::ALL[weights.sample(rng)]
+    }
+}
+
+/// A powerup that the player has collected, whose effect lasts until it expires
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ActivePowerup {
+    pub kind: PowerupKind,
+    /// The time (since the start of the game) at which the effect ends
+    pub expires_at: Duration,
+    /// How long the effect lasts in total
+    pub duration: Duration,
+}
+
+impl ActivePowerup {
+    /// Returns the part of the effect that is left at the given time, from 1 (just collected) to
+    /// 0 (expired)
+    pub fn fraction_left(&self, current_time: Duration) -> f32 {
+        let left = self.expires_at.checked_sub(current_time).unwrap_or_default();
+        (left.as_secs_f32() / self.duration.as_secs_f32()).clamp(0.0, 1.0)
+    }
+}
+
+/// This is the struct containing information about a powerup
+#[derive(Serialize, Deserialize)]
+pub struct Powerup {
+    /// The seconds left before the powerup disappears
+    pub ttl: f32,
+    /// The seconds the powerup lasted when it appeared, it shrinks as `ttl` approaches 0
+    pub lifetime: f32,
+    pub kind: PowerupKind,
+    pub color: u8,
+    pub position: Point,
+}
+
+impl Powerup {
+    pub fn new(kind: PowerupKind, position: Point, ttl: f32) -> Powerup {
+        Powerup {
+            ttl,
+            lifetime: ttl,
+            kind,
+            color: 0,
+            position,
+        }
+    }
+
+    pub fn random<R: Rng>(rng: &mut R, bounds: Size, ttl: f32, weights: &PowerupWeights) -> Powerup {
+        Powerup::new(PowerupKind::random(rng, weights), Point::random(rng, bounds), ttl)
+    }
+
+    pub fn update(&mut self, elapsed_time: f32) {
+        self.ttl -= elapsed_time;
+        self.color = if self.color < u8::MAX {
+            self.color + 1
+        } else {
+            0
+        }
+    }
+}
+
+impl Position for Powerup {
+    fn x(&self) -> f32 {
+        self.position.x
+    }
+    fn y(&self) -> f32 {
+        self.position.y
+    }
+    fn x_mut(&mut self) -> &mut f32 {
+        &mut self.position.x
+    }
+    fn y_mut(&mut self) -> &mut f32 {
+        &mut self.position.y
+    }
+
+    fn position(&self) -> Point {
+        Point::new(self.x(), self.y())
+    }
+}
+
+impl Collide for Powerup {
+    fn radius(&self) -> f32 {
+        POWERUP_SIZE * (self.ttl / self.lifetime)
+    }
+}
+
+#[test]
+fn test_powerup_weights() {
+    use rand::SeedableRng;
+
+    // Kinds with a weight of 0 never spawn
+    let mut rng = crate::GameRng::seed_from_u64(0);
+    let weights = PowerupWeights { magnet: 0.0, extra_life: 0.0, ..PowerupWeights::default() };
+    let kinds: Vec<_> = (0..1000).map(|_| PowerupKind::random(&mut rng, &weights)).collect();
+    assert!(!kinds.contains(&PowerupKind::Magnet) && !kinds.contains(&PowerupKind::ExtraLife));
+    assert!(PowerupKind::ALL.iter().filter(|kind| weights.weight(**kind) > 0.0).all(|kind| kinds.contains(kind)));
+
+    let only_bombs = PowerupWeights {
+        shield: 0.0,
+        time_slow: 0.0,
+        triple_shot: 0.0,
+        homing_missiles: 0.0,
+        coolant: 0.0,
+        smart_bomb: 1.0,
+        magnet: 0.0,
+        extra_life: 0.0,
+    };
+    assert_eq!(PowerupKind::random(&mut rng, &only_bombs), PowerupKind::SmartBomb);
+}
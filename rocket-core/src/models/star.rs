@@ -1,12 +1,14 @@
 use std;
 use rand::Rng;
-use geometry::{Advance, Position, Point, Size, Vector};
-use geometry_derive::{Advance, Position};
+use geometry::{Advance, Interpolate, Position, Point, Size, Vector};
+use geometry_derive::{Advance, Interpolate, Position};
+use serde::{Deserialize, Serialize};
 
 /// Stars glide from right to left across the screen in the background
-#[derive(Advance, Position)]
+#[derive(Advance, Position, Interpolate, Serialize, Deserialize)]
 pub struct Star {
     vector: Vector,
+    previous_position: Point,
     pub speed: f32,
     pub size: f32,
 }
@@ -21,6 +23,7 @@ impl Star {
 
         Star {
             vector: Vector::new(point, std::f32::consts::PI),
+            previous_position: point,
             speed: rng.gen_range(1.0 .. 3.0),
             size: rng.gen_range(2.0 .. 5.0),
         }
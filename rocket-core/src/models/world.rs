@@ -0,0 +1,88 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    geometry::{torus, Point, Position, Size},
+    models::{Boss, Bullet, Enemy, EnemyBullet, Particle, Player, Powerup, Star},
+};
+
+const MAX_STARS: usize = 100;
+// The amount of rows and columns of candidate spots checked by `safest_position`
+const SAFE_SPOT_GRID: usize = 8;
+
+/// A model that contains the other models and renders them
+#[derive(Serialize, Deserialize)]
+pub struct World {
+    pub player: Player,
+    pub particles: Vec<Particle>,
+    pub powerups: Vec<Powerup>,
+    pub bullets: Vec<Bullet>,
+    pub enemies: Vec<Enemy>,
+    pub enemy_bullets: Vec<EnemyBullet>,
+    /// The boss, while one is being fought
+    pub boss: Option<Boss>,
+    pub stars: Vec<Star>,
+    pub size: Size,
+}
+
+impl World {
+    /// Returns a new world of the given size
+    pub fn new<R: Rng>(rng: &mut R, size: Size) -> World {
+        World {
+            player: Player::random(rng, size),
+            particles: Vec::with_capacity(1000),
+            powerups: vec![],
+            bullets: vec![],
+            enemies: vec![],
+            enemy_bullets: vec![],
+            boss: None,
+            stars: (0..MAX_STARS).map(|_| Star::new(size, rng)).collect(),
+            size,
+        }
+    }
+
+    /// Returns the spot of the world that is furthest away from every enemy (and the boss)
+    ///
+    /// The candidates are the centers of the cells of a grid laid over the world, and distances
+    /// are measured the shortest way around, since the world wraps around
+    pub fn safest_position(&self) -> Point {
+        let Size { width, height } = self.size;
+        let cell = Size::new(width / SAFE_SPOT_GRID as f32, height / SAFE_SPOT_GRID as f32);
+        let candidates = (0..SAFE_SPOT_GRID).flat_map(|row| {
+            (0..SAFE_SPOT_GRID).map(move |column| {
+                Point::new((column as f32 + 0.5) * cell.width, (row as f32 + 0.5) * cell.height)
+            })
+        });
+
+        let closest_enemy = |spot: Point| {
+            self.enemies
+                .iter()
+                .map(|enemy| enemy.position())
+                .chain(self.boss.as_ref().map(|boss| boss.position()))
+                .map(|position| torus::squared_distance(position, spot, self.size))
+                .fold(f32::INFINITY, f32::min)
+        };
+
+        // Ties (e.g. when there are no enemies) go to the first candidate with the largest distance
+        let mut best = (Point::new(width / 2.0, height / 2.0), f32::NEG_INFINITY);
+        for spot in candidates {
+            let distance = closest_enemy(spot);
+            if distance > best.1 {
+                best = (spot, distance);
+            }
+        }
+        best.0
+    }
+}
+
+#[test]
+fn test_safest_position() {
+    use rand::SeedableRng;
+    use crate::{geometry::Vector, models::EnemyKind, GameRng};
+
+    let mut world = World::new(&mut GameRng::seed_from_u64(0), Size::new(800.0, 800.0));
+    world.enemies.push(Enemy::new(EnemyKind::Chaser, Vector::new(Point::new(50.0, 50.0), 0.0)));
+    // The opposite corner of the world wraps around to be close to the enemy, so the middle of
+    // the world is the safest spot
+    assert_eq!(world.safest_position(), Point::new(450.0, 450.0));
+}
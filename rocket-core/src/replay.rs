@@ -0,0 +1,240 @@
+//! Recording and playback of games
+//!
+//! Since the game only depends on its seed and the actions of the player, a `Replay` only needs
+//! to store those (together with the size of the world, the config the game was tuned with and
+//! the waves of enemies) to reproduce a whole game. Actions are stored once per update, so replays
+//! must be played with a fixed timestep (see `TIMESTEP`).
+//!
+//! The file format is small and simple. All numbers are little endian:
+//!
+//! * The magic bytes `RKTR`, followed by the version of the format (a `u8`)
+//! * The seed (a `u64`), and the width and height of the world (two `f32`s)
+//! * The config, as JSON preceded by its length in bytes (a `u32`)
+//! * The waves, stored like the config
+//! * The actions, stored as runs of identical actions: the length of the run (a `u16`) followed by
+//!   the rotation and thrust (two `f32`s) and the digital actions as bit flags (a `u8`)
+
+use std::convert::TryFrom;
+use std::io::{self, Read, Write};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{config::GameConfig, controllers::Actions, geometry::Size, waves::Waves};
+
+const MAGIC: &[u8; 4] = b"RKTR";
+const VERSION: u8 = 1;
+
+// Bit flags used to store the digital part of `Actions`
+const SHOOT: u8 = 1;
+
+// The length of a run of actions, in bytes
+const RUN_LENGTH: usize = 11;
+
+/// The seed, world size, config, waves and actions of a game
+pub struct Replay {
+    pub seed: u64,
+    pub size: Size,
+    pub config: GameConfig,
+    pub waves: Waves,
+    actions: Vec<Actions>,
+}
+
+impl Replay {
+    /// Returns an empty replay of a game with the given seed, world size, config and waves
+    pub fn new(seed: u64, size: Size, config: GameConfig, waves: Waves) -> Replay {
+        Replay {
+            seed,
+            size,
+            config,
+            waves,
+            actions: Vec::new(),
+        }
+    }
+
+    /// Records the actions in effect during the next update
+    pub fn record(&mut self, actions: &Actions) {
+        self.actions.push(*actions);
+    }
+
+    /// Returns the actions of every recorded update
+    pub fn actions(&self) -> &[Actions] {
+        &self.actions
+    }
+
+    /// Writes the replay in the format described in the module documentation
+    pub fn write_to<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&self.seed.to_le_bytes())?;
+        writer.write_all(&self.size.width.to_le_bytes())?;
+        writer.write_all(&self.size.height.to_le_bytes())?;
+        write_json(&mut writer, &self.config)?;
+        write_json(&mut writer, &self.waves)?;
+
+        let mut actions = self.actions.iter().peekable();
+        while let Some(current) = actions.next() {
+            let mut run: u16 = 1;
+            while run < u16::MAX && actions.peek() == Some(&current) {
+                actions.next();
+                run += 1;
+            }
+            writer.write_all(&run.to_le_bytes())?;
+            writer.write_all(&encode(current))?;
+        }
+
+        Ok(())
+    }
+
+    /// Reads a replay in the format described in the module documentation
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Replay> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a replay file"));
+        }
+
+        let mut version = [0; 1];
+        reader.read_exact(&mut version)?;
+        if version[0] != VERSION {
+            return Err(invalid_data(&format!("unsupported replay version {}", version[0])));
+        }
+
+        let mut seed = [0; 8];
+        let mut width = [0; 4];
+        let mut height = [0; 4];
+        reader.read_exact(&mut seed)?;
+        reader.read_exact(&mut width)?;
+        reader.read_exact(&mut height)?;
+        let size = Size::new(f32::from_le_bytes(width), f32::from_le_bytes(height));
+        if !(size.width > 0.0 && size.height > 0.0) {
+            return Err(invalid_data("the size of the world must be positive"));
+        }
+
+        let config: GameConfig = read_json(&mut reader)?;
+        config.validate().map_err(|e| invalid_data(&e))?;
+        let waves: Waves = read_json(&mut reader)?;
+        waves.validate().map_err(|e| invalid_data(&e))?;
+
+        let mut replay = Replay::new(u64::from_le_bytes(seed), size, config, waves);
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest)?;
+        if rest.len() % RUN_LENGTH != 0 {
+            return Err(invalid_data("truncated replay file"));
+        }
+        for chunk in rest.chunks(RUN_LENGTH) {
+            let run = u16::from_le_bytes([chunk[0], chunk[1]]);
+            let actions = decode(&chunk[2..])?;
+            replay.actions.extend((0..run).map(|_| actions));
+        }
+
+        Ok(replay)
+    }
+}
+
+// When a replay is part of another file (e.g. a saved game), it is stored in its own compact
+// format rather than as a list of actions
+impl Serialize for Replay {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for Replay {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Replay, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        Replay::read_from(&bytes[..]).map_err(de::Error::custom)
+    }
+}
+
+fn encode(actions: &Actions) -> [u8; RUN_LENGTH - 2] {
+    let mut bytes = [0; RUN_LENGTH - 2];
+    bytes[0..4].copy_from_slice(&actions.rotation.to_le_bytes());
+    bytes[4..8].copy_from_slice(&actions.thrust.to_le_bytes());
+    if actions.shoot {
+        bytes[8] |= SHOOT;
+    }
+    bytes
+}
+
+fn decode(bytes: &[u8]) -> io::Result<Actions> {
+    let rotation = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    let thrust = f32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+    let flags = bytes[8];
+    if !rotation.is_finite() || !thrust.is_finite() {
+        return Err(invalid_data("invalid analog actions in replay file"));
+    }
+    if flags & !SHOOT != 0 {
+        return Err(invalid_data("unknown actions in replay file"));
+    }
+
+    Ok(Actions {
+        rotation,
+        thrust,
+        shoot: flags & SHOOT != 0,
+    })
+}
+
+// Writes a value as JSON, preceded by its length
+fn write_json<W: Write, T: Serialize>(writer: &mut W, value: &T) -> io::Result<()> {
+    let json = serde_json::to_vec(value).map_err(io::Error::from)?;
+    let length = u32::try_from(json.len()).map_err(|_| invalid_data("too much data for a replay file"))?;
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(&json)
+}
+
+// Reads a value written by `write_json`
+fn read_json<R: Read, T: de::DeserializeOwned>(reader: &mut R) -> io::Result<T> {
+    let mut length = [0; 4];
+    reader.read_exact(&mut length)?;
+    let mut json = Vec::new();
+    reader.by_ref().take(u32::from_le_bytes(length) as u64).read_to_end(&mut json)?;
+    serde_json::from_slice(&json).map_err(|e| invalid_data(&format!("invalid replay file: {}", e)))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[test]
+fn test_replay_roundtrip() {
+    let mut config = GameConfig::default();
+    config.gun.bullets_per_second = 12.0;
+    let mut waves = Waves { pause: 1.0, ..Waves::default() };
+    waves.waves.truncate(1);
+    let mut replay = Replay::new(42, Size::new(1024.0, 576.0), config, waves.clone());
+    let shoot = Actions { shoot: true, ..Actions::default() };
+    let turn = Actions { rotation: 0.5, thrust: 1.0, ..Actions::default() };
+    for actions in [shoot; 70_000].iter().chain(&[turn, Actions::default(), turn]) {
+        replay.record(actions);
+    }
+
+    let mut bytes = Vec::new();
+    replay.write_to(&mut bytes).unwrap();
+    let config_length = serde_json::to_vec(&config).unwrap().len();
+    let waves_length = serde_json::to_vec(&waves).unwrap().len();
+    assert_eq!(bytes.len(), 21 + 4 + config_length + 4 + waves_length + 5 * RUN_LENGTH);
+
+    let read = Replay::read_from(&bytes[..]).unwrap();
+    assert_eq!(read.seed, 42);
+    assert_eq!(read.size.width, 1024.0);
+    assert_eq!(read.config, config);
+    assert_eq!(read.waves, waves);
+    assert!(read.actions() == replay.actions());
+
+    assert!(Replay::read_from(&bytes[..bytes.len() - 1]).is_err());
+    assert!(Replay::read_from(&b"nope"[..]).is_err());
+
+    // A config or waves that don't make sense are rejected, like when they're read from a file
+    let mut bad = Replay::new(42, Size::new(1024.0, 576.0), config, waves.clone());
+    bad.config.gun.bullets_per_second = -1.0;
+    let mut bytes = Vec::new();
+    bad.write_to(&mut bytes).unwrap();
+    assert!(Replay::read_from(&bytes[..]).is_err());
+    let mut bad = Replay::new(42, Size::new(1024.0, 576.0), config, waves);
+    bad.waves.waves.clear();
+    let mut bytes = Vec::new();
+    bad.write_to(&mut bytes).unwrap();
+    assert!(Replay::read_from(&bytes[..]).is_err());
+}
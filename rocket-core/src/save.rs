@@ -0,0 +1,154 @@
+//! Saving a game in progress, so it can be resumed later
+//!
+//! A saved game contains everything needed to continue exactly where the game was left: the state
+//! of the world, the timers and scheduled timeouts, the random number generator and the recording
+//! of the game so far. It is stored as JSON.
+//!
+//! Games are written from a `GameToSave`, which borrows the game that is being played, and read
+//! back as a `SavedGame`, which owns it.
+
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{controllers::TimeController, game_state::GameState, replay::Replay, GameRng};
+
+const VERSION: u32 = 1;
+
+/// A game in progress that is about to be saved
+#[derive(Serialize)]
+pub struct GameToSave<'a> {
+    version: u32,
+    pub seed: u64,
+    pub state: &'a GameState,
+    pub time_controller: &'a TimeController,
+    pub rng: &'a GameRng,
+    pub recording: &'a Replay,
+}
+
+impl<'a> GameToSave<'a> {
+    /// Returns a game to save, consisting of the given parts
+    pub fn new(
+        seed: u64,
+        state: &'a GameState,
+        time_controller: &'a TimeController,
+        rng: &'a GameRng,
+        recording: &'a Replay,
+    ) -> GameToSave<'a> {
+        GameToSave {
+            version: VERSION,
+            seed,
+            state,
+            time_controller,
+            rng,
+            recording,
+        }
+    }
+
+    /// Writes the game as JSON
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer(writer, self).map_err(io::Error::from)
+    }
+}
+
+/// A game that was saved, and can be resumed
+#[derive(Deserialize)]
+pub struct SavedGame {
+    /// The version of the format, to reject saves made by incompatible versions of the game
+    version: u32,
+    pub seed: u64,
+    pub state: GameState,
+    pub time_controller: TimeController,
+    pub rng: GameRng,
+    pub recording: Replay,
+}
+
+impl SavedGame {
+    /// Reads a saved game written by `GameToSave::write_to`
+    ///
+    /// The config, waves and timers are checked like when they're created, so a corrupt saved game
+    /// is rejected instead of breaking the game once it's resumed
+    pub fn read_from<R: Read>(reader: R) -> io::Result<SavedGame> {
+        let saved: SavedGame = serde_json::from_reader(reader).map_err(io::Error::from)?;
+        if saved.version != VERSION {
+            let message = format!("unsupported saved game version {}", saved.version);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, message));
+        }
+        saved
+            .state
+            .config
+            .validate()
+            .and_then(|()| saved.state.waves.definitions().validate())
+            .and_then(|()| saved.time_controller.validate(&saved.state.config))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(saved)
+    }
+}
+
+#[test]
+fn test_resumed_game_is_identical() {
+    use rand::SeedableRng;
+    use crate::{controllers::{Actions, CollisionsController, Timeout, TIMESTEP}, geometry::{Position, Size}};
+
+    fn play(state: &mut GameState, time_controller: &mut TimeController, rng: &mut GameRng, ticks: u32) {
+        let actions = Actions { rotation: 1.0, shoot: true, ..Actions::default() };
+        let mut events = Vec::new();
+        for _ in 0..ticks {
+            time_controller.update_seconds(TIMESTEP, &actions, state, &mut events, rng);
+            CollisionsController::handle_collisions(state, time_controller, &mut events);
+        }
+    }
+
+    let size = Size::new(800.0, 600.0);
+    let mut rng = GameRng::seed_from_u64(5);
+    let mut state = GameState::new(size, &mut rng);
+    let mut time_controller = TimeController::new(&state.config);
+    state.reset(&mut rng);
+    play(&mut state, &mut time_controller, &mut rng, 500);
+    time_controller.schedule_timeout(std::time::Duration::from_secs(1), Timeout::ShowGameOverScreen);
+
+    let recording = Replay::new(5, size, state.config, state.waves.definitions().clone());
+    let mut bytes = Vec::new();
+    GameToSave::new(5, &state, &time_controller, &rng, &recording).write_to(&mut bytes).unwrap();
+    let mut resumed = SavedGame::read_from(&bytes[..]).unwrap();
+
+    play(&mut state, &mut time_controller, &mut rng, 500);
+    play(&mut resumed.state, &mut resumed.time_controller, &mut resumed.rng, 500);
+
+    let positions = |state: &GameState| -> Vec<_> {
+        state.world.enemies.iter().map(|e| e.position()).collect()
+    };
+    assert!(positions(&state) == positions(&resumed.state));
+    assert_eq!(state.score, resumed.state.score);
+    assert!(resumed.state.message.is_some());
+}
+
+#[test]
+fn test_corrupt_saves_are_rejected() {
+    use rand::SeedableRng;
+    use serde_json::{json, Value};
+    use crate::geometry::Size;
+
+    let size = Size::new(800.0, 600.0);
+    let mut rng = GameRng::seed_from_u64(5);
+    let state = GameState::new(size, &mut rng);
+    let time_controller = TimeController::new(&state.config);
+    let recording = Replay::new(5, size, state.config, state.waves.definitions().clone());
+    let mut bytes = Vec::new();
+    GameToSave::new(5, &state, &time_controller, &rng, &recording).write_to(&mut bytes).unwrap();
+    assert!(SavedGame::read_from(&bytes[..]).is_ok());
+
+    let corrupt = |edit: &dyn Fn(&mut Value)| {
+        let mut save: Value = serde_json::from_slice(&bytes).unwrap();
+        edit(&mut save);
+        SavedGame::read_from(serde_json::to_vec(&save).unwrap().as_slice()).err().unwrap().to_string()
+    };
+
+    // A timer that never advances would loop forever, one from the future would underflow
+    let error = corrupt(&|save| save["time_controller"]["shoot_timer"]["interval"] = json!({ "secs": 0, "nanos": 0 }));
+    assert!(error.contains("invalid shoot timer"));
+    let error = corrupt(&|save| save["time_controller"]["trail_timer"]["last_triggered"] = json!({ "secs": 60, "nanos": 0 }));
+    assert!(error.contains("invalid trail timer"));
+    let error = corrupt(&|save| save["state"]["config"]["gun"]["bullets_per_second"] = json!(0.0));
+    assert!(error.contains("gun.bullets_per_second"));
+}
@@ -0,0 +1,99 @@
+//! Running whole games without a window, e.g. to benchmark balance changes
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use rand::SeedableRng;
+use serde::Serialize;
+
+use crate::{
+    bots::Policy,
+    config::GameConfig,
+    controllers::{CollisionsController, Event, TimeController, TIMESTEP},
+    game_state::GameState,
+    geometry::Size,
+    waves::{WaveDirector, Waves},
+    GameRng,
+};
+
+/// The outcome of a simulated game
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    pub seed: u64,
+    pub score: u32,
+    /// Whether the player lost all their lives before the time ran out
+    pub died: bool,
+    /// The amount of seconds until the player lost their last life (or the time ran out)
+    pub survival_time: f32,
+    /// The lives left at the end of the game
+    pub lives: u32,
+    pub enemies_killed: u32,
+    pub powerups_collected: u32,
+    /// The wave that was being played when the game ended
+    pub wave: u32,
+    /// How many times each kind of event happened
+    pub events: BTreeMap<String, u32>,
+}
+
+/// Plays a game with the given seed, world size, config and waves, controlled by `policy`
+///
+/// The game runs at a fixed timestep until the player loses all lives or `duration` has passed,
+/// whichever comes first
+pub fn simulate(
+    seed: u64,
+    size: Size,
+    config: GameConfig,
+    waves: Waves,
+    duration: Duration,
+    policy: &mut dyn Policy,
+) -> Summary {
+    let mut rng = GameRng::seed_from_u64(seed);
+    let mut state = GameState::with_config(size, config, &mut rng);
+    state.waves = WaveDirector::new(waves);
+    let mut time_controller = TimeController::new(&config);
+    let mut events = Vec::new();
+    let mut event_counts = BTreeMap::<Event, u32>::new();
+    state.reset(&mut rng);
+
+    while !state.is_game_over() && time_controller.current_time() < duration {
+        let actions = policy.actions(&state);
+        time_controller.update_seconds(TIMESTEP, &actions, &mut state, &mut events, &mut rng);
+        CollisionsController::handle_collisions(&mut state, &mut time_controller, &mut events);
+
+        for event in events.drain(..) {
+            *event_counts.entry(event).or_insert(0) += 1;
+        }
+    }
+
+    Summary {
+        seed,
+        score: state.score,
+        died: state.is_game_over(),
+        survival_time: time_controller.current_time().as_secs_f32(),
+        lives: state.lives,
+        enemies_killed: state.enemies_killed,
+        powerups_collected: state.powerups_collected,
+        wave: state.waves.wave(),
+        events: event_counts
+            .into_iter()
+            .map(|(event, count)| (format!("{:?}", event), count))
+            .collect(),
+    }
+}
+
+#[test]
+fn test_simulation_is_reproducible() {
+    use crate::bots::bot_by_name;
+
+    let size = Size::new(1024.0, 576.0);
+    let run = || {
+        let mut bot = bot_by_name("random", 7).unwrap();
+        simulate(7, size, GameConfig::default(), Waves::default(), Duration::from_secs(30), &mut *bot)
+    };
+
+    let (a, b) = (run(), run());
+    assert_eq!(a.score, b.score);
+    assert_eq!(a.survival_time, b.survival_time);
+    assert_eq!(a.events, b.events);
+    assert!(a.score >= 10 * a.enemies_killed);
+}
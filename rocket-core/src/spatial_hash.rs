@@ -0,0 +1,146 @@
+//! A uniform grid over the world, to find the objects near a point without checking all of them
+//!
+//! The world is split into square cells, and every object is stored in the cell that contains its
+//! center. A query only visits the cells that are close enough to contain an object touching the
+//! queried circle, wrapping around the edges of the world like everything else in the game.
+//!
+//! The grid is meant to be rebuilt whenever the objects move (usually once per update), which is
+//! cheap: building it is linear in the amount of objects, and it reuses its memory.
+
+use geometry::{Point, Size};
+
+/// A grid of objects, which are identified by their index in the slice they were built from
+pub struct SpatialHash {
+    /// The size of a cell, which is stretched a bit so that the cells fit the world exactly
+    cell_size: Size,
+    columns: usize,
+    rows: usize,
+    /// The radius of the biggest object, which queries have to take into account
+    max_radius: f32,
+    /// The objects of cell `i` are `entries[cell_starts[i]..cell_starts[i + 1]]`
+    cell_starts: Vec<usize>,
+    entries: Vec<usize>,
+}
+
+impl SpatialHash {
+    /// Returns an empty grid covering a world of the given size, with cells of about `cell_size`
+    /// pixels
+    pub fn new(size: Size, cell_size: f32) -> SpatialHash {
+        let columns = ((size.width / cell_size).floor() as usize).max(1);
+        let rows = ((size.height / cell_size).floor() as usize).max(1);
+        SpatialHash {
+            cell_size: Size::new(size.width / columns as f32, size.height / rows as f32),
+            columns,
+            rows,
+            max_radius: 0.0,
+            cell_starts: vec![0; columns * rows + 1],
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns a grid of the given objects, described by their position and radius
+    pub fn from_objects(size: Size, cell_size: f32, objects: impl Iterator<Item = (Point, f32)>) -> SpatialHash {
+        let mut hash = SpatialHash::new(size, cell_size);
+        hash.rebuild(objects);
+        hash
+    }
+
+    /// Replaces the objects in the grid
+    pub fn rebuild(&mut self, objects: impl Iterator<Item = (Point, f32)>) {
+        // A counting sort of the objects by cell: count the objects per cell, turn the counts
+        // into the start of each cell and then put every object in its place
+        self.max_radius = 0.0;
+        let cells: Vec<usize> = objects
+            .map(|(position, radius)| {
+                self.max_radius = self.max_radius.max(radius);
+                self.cell_of(position)
+            })
+            .collect();
+
+        self.cell_starts.iter_mut().for_each(|start| *start = 0);
+        for &cell in &cells {
+            self.cell_starts[cell + 1] += 1;
+        }
+        for i in 1..self.cell_starts.len() {
+            self.cell_starts[i] += self.cell_starts[i - 1];
+        }
+
+        let mut next = self.cell_starts.clone();
+        self.entries.clear();
+        self.entries.resize(cells.len(), 0);
+        for (index, &cell) in cells.iter().enumerate() {
+            self.entries[next[cell]] = index;
+            next[cell] += 1;
+        }
+    }
+
+    /// Calls `f` with the index of every object that might touch the circle at `position` with
+    /// the given radius, each of them once
+    ///
+    /// Some of the objects might be too far away to touch the circle, so the caller still needs
+    /// to check for collisions
+    pub fn query(&self, position: Point, radius: f32, mut f: impl FnMut(usize)) {
+        let reach = radius + self.max_radius;
+        let columns = wrapped_range(position.x, reach, self.cell_size.width, self.columns);
+        let rows = wrapped_range(position.y, reach, self.cell_size.height, self.rows);
+        for row in rows {
+            for column in columns.clone() {
+                let cell = row * self.columns + column;
+                for &index in &self.entries[self.cell_starts[cell]..self.cell_starts[cell + 1]] {
+                    f(index);
+                }
+            }
+        }
+    }
+
+    /// Returns the indices of the objects that might touch the given circle, see `query`
+    pub fn candidates(&self, position: Point, radius: f32) -> Vec<usize> {
+        let mut candidates = Vec::new();
+        self.query(position, radius, |index| candidates.push(index));
+        candidates
+    }
+
+    // Returns the cell that contains the given position, wrapping positions outside of the world
+    fn cell_of(&self, position: Point) -> usize {
+        let column = (position.x / self.cell_size.width).floor() as i64;
+        let row = (position.y / self.cell_size.height).floor() as i64;
+        let column = column.rem_euclid(self.columns as i64) as usize;
+        let row = row.rem_euclid(self.rows as i64) as usize;
+        row * self.columns + column
+    }
+}
+
+// Returns the cells along one axis that are within `reach` of `center`, wrapping around `cells`
+// and visiting every cell at most once
+fn wrapped_range(center: f32, reach: f32, cell_size: f32, cells: usize) -> impl Iterator<Item = usize> + Clone {
+    let first = ((center - reach) / cell_size).floor() as i64;
+    let last = ((center + reach) / cell_size).floor() as i64;
+    let count = ((last - first + 1) as usize).min(cells);
+    (first..first + count as i64).map(move |cell| cell.rem_euclid(cells as i64) as usize)
+}
+
+#[test]
+fn test_spatial_hash() {
+    let size = Size::new(1000.0, 500.0);
+    let objects = [
+        (Point::new(100.0, 100.0), 10.0),
+        (Point::new(120.0, 100.0), 10.0),
+        (Point::new(800.0, 400.0), 10.0),
+        (Point::new(995.0, 250.0), 10.0),
+    ];
+    let hash = SpatialHash::from_objects(size, 64.0, objects.iter().cloned());
+
+    let mut near = hash.candidates(Point::new(110.0, 110.0), 5.0);
+    near.sort_unstable();
+    assert_eq!(near, vec![0, 1]);
+    assert!(hash.candidates(Point::new(500.0, 250.0), 5.0).is_empty());
+
+    // Queries wrap around the edges of the world
+    assert_eq!(hash.candidates(Point::new(2.0, 250.0), 5.0), vec![3]);
+    assert_eq!(hash.candidates(Point::new(0.0, 250.0), 60.0), vec![3]);
+
+    // A query covering the whole world returns every object once
+    let mut all = hash.candidates(Point::new(0.0, 0.0), 5000.0);
+    all.sort_unstable();
+    assert_eq!(all, vec![0, 1, 2, 3]);
+}
@@ -1,3 +1,4 @@
+use std::f32;
 use std::time::Duration;
 
 use crate::{
@@ -10,6 +11,19 @@ pub fn duration_to_seconds(d: Duration) -> f32 {
     d.as_secs() as f32 + d.subsec_nanos() as f32 * 1e-9
 }
 
+/// Returns the angle to turn from the direction `from` to the direction `to` (in radians), taking
+/// the shortest way: the result is between -PI and PI
+pub fn angle_difference(from: f32, to: f32) -> f32 {
+    let delta = (to - from) % (2.0 * f32::consts::PI);
+    if delta > f32::consts::PI {
+        delta - 2.0 * f32::consts::PI
+    } else if delta < -f32::consts::PI {
+        delta + 2.0 * f32::consts::PI
+    } else {
+        delta
+    }
+}
+
 /// Optimized version of `Vec::retain`
 ///
 /// We achieve better performance by renouncing to keep the original order of the `Vec`
@@ -32,7 +46,7 @@ where
 pub fn make_explosion(particles: &mut Vec<Particle>, position: &Point, intensity: u8) {
     for rotation in itertools_num::linspace(0.0, 2.0 * ::std::f32::consts::PI, 30) {
         for ttl in (1..intensity).map(|x| (x as f32) / 10.0) {
-            particles.push(Particle::new(Vector::new(position.clone(), rotation), ttl));
+            particles.push(Particle::new(Vector::new(*position, rotation), ttl));
         }
     }
 }
@@ -0,0 +1,430 @@
+//! Enemies come in waves, which get harder as the game goes on
+//!
+//! The waves are defined in a TOML file (the `waves.toml` file at the root of the repository has
+//! the ones the game uses by default). Each wave consists of groups of enemies, which spawn one
+//! after another. A wave is cleared once all of its enemies are destroyed, which earns a bonus,
+//! and the next wave starts after a pause.
+//!
+//! Once the defined waves run out they start over, with more enemies each time, so the game never
+//! runs out of waves.
+//!
+//! Every few waves, a boss shows up after the pause instead of the next wave. Defeating it (and
+//! its minions) earns a big bonus, after which the waves continue.
+
+use std::io::{self, Read};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{at_most, non_negative, MAX_SECONDS};
+use crate::models::EnemyKind;
+
+// The most enemies a group can have, both as defined and after growing in later rounds, so a
+// wave never queues more spawns than the game can handle
+const MAX_GROUP_COUNT: u32 = 1000;
+
+// The largest values that make sense for the rest of the definitions, way beyond anything
+// playable, but small enough that the speed of the enemies and the bonuses stay within range
+const MAX_DIFFICULTY_PER_WAVE: f32 = 10.0;
+const MAX_GROWTH: f32 = 100.0;
+const MAX_BONUS: u32 = 100_000;
+const MAX_BOSS_HEALTH: u32 = 10_000;
+
+/// Where the enemies of a group appear
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SpawnPattern {
+    /// Anywhere, except too close to the player
+    #[default]
+    Random,
+    /// Evenly spread on a circle around the player
+    Ring,
+    /// Evenly spread along the left and right edges of the world
+    Edges,
+}
+
+/// A group of enemies of the same kind, within a wave
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Group {
+    /// The kind of the enemies, or a random kind for each enemy (depending on the difficulty)
+    /// when left out
+    #[serde(default)]
+    pub kind: Option<EnemyKind>,
+    pub count: u32,
+    #[serde(default)]
+    pub pattern: SpawnPattern,
+    /// The seconds between the enemies of the group, 0 spawns them all at once
+    #[serde(default = "default_interval")]
+    pub interval: f32,
+}
+
+fn default_interval() -> f32 {
+    1.0
+}
+
+/// A wave of enemies, whose groups spawn one after another
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Wave {
+    pub groups: Vec<Group>,
+}
+
+/// The definition of every wave
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Waves {
+    /// The seconds between clearing a wave and the start of the next one
+    pub pause: f32,
+    /// The points earned for clearing a wave, multiplied by the wave's number
+    pub clear_bonus: u32,
+    /// The difficulty (which speeds enemies up) gained with each wave
+    pub difficulty_per_wave: f32,
+    /// How many more enemies (as a fraction of the defined amount) each group has every time the
+    /// waves start over
+    pub endless_growth: f32,
+    /// A boss shows up after every wave whose number is a multiple of this (0 means never)
+    pub boss_every: u32,
+    /// The health of the first boss, later bosses have this much more each
+    pub boss_health: u32,
+    /// The points earned for defeating a boss, multiplied by the boss' number
+    pub boss_bonus: u32,
+    pub waves: Vec<Wave>,
+}
+
+impl Default for Waves {
+    fn default() -> Waves {
+        Waves::from_toml(include_str!("../../waves.toml")).unwrap()
+    }
+}
+
+impl Waves {
+    /// Parses wave definitions from TOML, and checks that they make sense
+    pub fn from_toml(toml: &str) -> io::Result<Waves> {
+        let waves: Waves = toml::from_str(toml)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        waves
+            .validate()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(waves)
+    }
+
+    /// Reads wave definitions in TOML format, see `from_toml`
+    pub fn read_from<R: Read>(mut reader: R) -> io::Result<Waves> {
+        let mut toml = String::new();
+        reader.read_to_string(&mut toml)?;
+        Waves::from_toml(&toml)
+    }
+
+    /// Checks that the definitions make sense, returning a description of the first problem
+    pub fn validate(&self) -> Result<(), String> {
+        non_negative("pause", self.pause, MAX_SECONDS)?;
+        non_negative("difficulty_per_wave", self.difficulty_per_wave, MAX_DIFFICULTY_PER_WAVE)?;
+        non_negative("endless_growth", self.endless_growth, MAX_GROWTH)?;
+        at_most("clear_bonus", self.clear_bonus, MAX_BONUS)?;
+        at_most("boss_health", self.boss_health, MAX_BOSS_HEALTH)?;
+        at_most("boss_bonus", self.boss_bonus, MAX_BONUS)?;
+        if self.boss_every > 0 && self.boss_health == 0 {
+            return Err("boss_health must be at least 1".to_string());
+        }
+        if self.waves.is_empty() {
+            return Err("there must be at least one wave".to_string());
+        }
+        for (i, wave) in self.waves.iter().enumerate() {
+            if wave.groups.iter().all(|group| group.count == 0) {
+                return Err(format!("wave {} has no enemies", i + 1));
+            }
+            for group in &wave.groups {
+                if group.count > MAX_GROUP_COUNT {
+                    return Err(format!(
+                        "the groups of wave {} can have at most {} enemies, but one has {}",
+                        i + 1,
+                        MAX_GROUP_COUNT,
+                        group.count
+                    ));
+                }
+                non_negative(&format!("the interval of the groups of wave {}", i + 1), group.interval, MAX_SECONDS)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the wave with the given number, starting at 1
+    ///
+    /// Waves after the defined ones repeat them, with more enemies in each group (up to a limit)
+    pub fn wave(&self, number: u32) -> Wave {
+        let index = (number.max(1) - 1) as usize;
+        let round = (index / self.waves.len()) as f32;
+        let mut wave = self.waves[index % self.waves.len()].clone();
+        for group in &mut wave.groups {
+            let grown = (group.count as f32 * (1.0 + self.endless_growth * round)).ceil();
+            group.count = grown.min(MAX_GROUP_COUNT as f32) as u32;
+        }
+        wave
+    }
+}
+
+/// An enemy that is about to spawn
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Spawn {
+    /// The kind of enemy, or `None` for a random one
+    pub kind: Option<EnemyKind>,
+    pub pattern: SpawnPattern,
+    /// The position of the enemy within its group, and the size of the group
+    pub index: u32,
+    pub count: u32,
+    /// The seconds to wait after spawning this enemy, before spawning the next one
+    delay: f32,
+}
+
+/// What the director is doing
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum Phase {
+    /// Waiting for the next wave to start
+    Pause { left: f32 },
+    /// Spawning the enemies of the current wave
+    Spawning { queue: Vec<Spawn>, next: f32 },
+    /// Waiting for the player to destroy the enemies of the current wave
+    Fighting,
+    /// Waiting for the player to destroy the boss and its minions
+    BossFight,
+}
+
+/// Something that happened to the waves
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WaveEvent {
+    /// The wave with the given number started
+    Started(u32),
+    /// The wave with the given number was cleared, earning `bonus` points
+    Cleared { wave: u32, bonus: u32 },
+    /// A boss with the given health showed up
+    BossArrived { health: u32 },
+    /// The boss and its minions were destroyed, earning `bonus` points
+    BossDefeated { bonus: u32 },
+}
+
+/// Decides when and where enemies spawn, wave after wave
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WaveDirector {
+    definitions: Waves,
+    /// The number of the current wave, 0 before the first one starts
+    wave: u32,
+    phase: Phase,
+    /// The amount of bosses that showed up so far
+    bosses: u32,
+    /// Whether a boss shows up when the current pause ends
+    boss_is_next: bool,
+    /// The last time a wave was cleared or a boss defeated, if any
+    last_cleared: Option<WaveEvent>,
+}
+
+impl Default for WaveDirector {
+    fn default() -> WaveDirector {
+        WaveDirector::new(Waves::default())
+    }
+}
+
+impl WaveDirector {
+    /// Returns a director for the given waves, which starts the first one after a pause
+    pub fn new(definitions: Waves) -> WaveDirector {
+        let pause = definitions.pause;
+        WaveDirector {
+            definitions,
+            wave: 0,
+            phase: Phase::Pause { left: pause },
+            bosses: 0,
+            boss_is_next: false,
+            last_cleared: None,
+        }
+    }
+
+    /// Returns the definitions of the waves
+    pub fn definitions(&self) -> &Waves {
+        &self.definitions
+    }
+
+    /// Starts over from the first wave
+    pub fn restart(&mut self) {
+        *self = WaveDirector::new(self.definitions.clone());
+    }
+
+    /// Returns the number of the current wave, 0 before the first one starts
+    pub fn wave(&self) -> u32 {
+        self.wave
+    }
+
+    /// Whether the director is pausing before the next wave
+    pub fn is_between_waves(&self) -> bool {
+        matches!(self.phase, Phase::Pause { .. })
+    }
+
+    /// Whether a boss shows up when the current pause ends
+    pub fn boss_is_next(&self) -> bool {
+        self.boss_is_next
+    }
+
+    /// Returns the last `Cleared` or `BossDefeated` event, if any
+    pub fn last_cleared(&self) -> Option<WaveEvent> {
+        self.last_cleared
+    }
+
+    /// Advances the waves by `dt` seconds, given the amount of enemies that are still alive
+    /// (counting the boss, if there is one)
+    ///
+    /// Calls `spawn` for every enemy that should appear, and returns what happened to the waves
+    pub fn update(&mut self, dt: f32, enemies_alive: usize, mut spawn: impl FnMut(Spawn)) -> Option<WaveEvent> {
+        match self.phase {
+            Phase::Pause { ref mut left } => {
+                *left -= dt;
+                if *left > 0.0 {
+                    return None;
+                }
+
+                if self.boss_is_next {
+                    self.boss_is_next = false;
+                    self.bosses = self.bosses.saturating_add(1);
+                    self.phase = Phase::BossFight;
+                    let health = self.definitions.boss_health.saturating_mul(self.bosses);
+                    return Some(WaveEvent::BossArrived { health });
+                }
+
+                self.wave = self.wave.saturating_add(1);
+                let wave = self.definitions.wave(self.wave);
+                let mut queue = Vec::new();
+                for group in &wave.groups {
+                    queue.extend((0..group.count).map(|index| Spawn {
+                        kind: group.kind,
+                        pattern: group.pattern,
+                        index,
+                        count: group.count,
+                        delay: group.interval,
+                    }));
+                }
+                // Spawn from the back of the queue
+                queue.reverse();
+                self.phase = Phase::Spawning { queue, next: 0.0 };
+                Some(WaveEvent::Started(self.wave))
+            }
+            Phase::Spawning { ref mut queue, ref mut next } => {
+                *next -= dt;
+                while *next <= 0.0 {
+                    match queue.pop() {
+                        Some(enemy) => {
+                            spawn(enemy);
+                            *next += enemy.delay;
+                        }
+                        None => break,
+                    }
+                }
+
+                if queue.is_empty() {
+                    self.phase = Phase::Fighting;
+                }
+                None
+            }
+            Phase::Fighting if enemies_alive == 0 => {
+                let bonus = self.definitions.clear_bonus.saturating_mul(self.wave);
+                let every = self.definitions.boss_every;
+                self.boss_is_next = every > 0 && self.wave.is_multiple_of(every);
+                self.finish(WaveEvent::Cleared { wave: self.wave, bonus })
+            }
+            Phase::BossFight if enemies_alive == 0 => {
+                let bonus = self.definitions.boss_bonus.saturating_mul(self.bosses);
+                self.finish(WaveEvent::BossDefeated { bonus })
+            }
+            Phase::Fighting | Phase::BossFight => None,
+        }
+    }
+
+    // Pauses after a wave was cleared or a boss defeated
+    fn finish(&mut self, event: WaveEvent) -> Option<WaveEvent> {
+        self.last_cleared = Some(event);
+        self.phase = Phase::Pause { left: self.definitions.pause };
+        Some(event)
+    }
+}
+
+#[test]
+fn test_waves() {
+    let waves = Waves::from_toml(
+        "pause = 2.0\n\
+         clear_bonus = 100\n\
+         difficulty_per_wave = 0.1\n\
+         endless_growth = 0.5\n\
+         boss_every = 1\n\
+         boss_health = 20\n\
+         boss_bonus = 1000\n\
+         [[waves]]\n\
+         groups = [{ kind = \"Tank\", count = 2, interval = 0.0 }, { count = 3, pattern = \"Ring\" }]\n",
+    )
+    .unwrap();
+    assert_eq!(waves.wave(1).groups[1].count, 3);
+    // The second time around, groups are 50% bigger
+    assert_eq!(waves.wave(2).groups[1].count, 5);
+    // Groups stop growing at some point, however long the game goes on
+    assert_eq!(waves.wave(u32::MAX).groups[1].count, MAX_GROUP_COUNT);
+    let huge = Waves {
+        waves: vec![Wave { groups: vec![Group { count: u32::MAX, ..waves.waves[0].groups[0].clone() }] }],
+        ..waves.clone()
+    };
+    assert!(huge.validate().unwrap_err().contains("at most"));
+    let no_waves = "pause = 1.0\nclear_bonus = 0\ndifficulty_per_wave = 0.0\nendless_growth = 0.0\n\
+                    boss_every = 0\nboss_health = 0\nboss_bonus = 0\nwaves = []";
+    assert!(Waves::from_toml(no_waves).unwrap_err().to_string().contains("at least one wave"));
+    assert!(Waves::read_from(include_str!("../../waves.toml").as_bytes()).is_ok());
+
+    let mut director = WaveDirector::new(waves);
+    let mut spawned = Vec::new();
+    assert_eq!(director.update(2.0, 0, |s| spawned.push(s)), Some(WaveEvent::Started(1)));
+    // Both tanks spawn at once, the ring one per second
+    director.update(0.5, 0, |s| spawned.push(s));
+    assert_eq!(spawned.len(), 3);
+    director.update(2.0, 3, |s| spawned.push(s));
+    assert_eq!(spawned.len(), 5);
+    assert_eq!(director.update(0.1, 5, |s| spawned.push(s)), None);
+    assert_eq!(director.update(0.1, 0, |s| spawned.push(s)), Some(WaveEvent::Cleared { wave: 1, bonus: 100 }));
+    assert!(director.is_between_waves());
+
+    // Every wave is followed by a boss, which has to be defeated before the next wave
+    assert!(director.boss_is_next());
+    assert_eq!(director.update(2.0, 0, |s| spawned.push(s)), Some(WaveEvent::BossArrived { health: 20 }));
+    assert_eq!(director.update(2.0, 1, |s| spawned.push(s)), None);
+    assert_eq!(director.update(0.1, 0, |s| spawned.push(s)), Some(WaveEvent::BossDefeated { bonus: 1000 }));
+    assert_eq!(director.update(2.0, 0, |s| spawned.push(s)), Some(WaveEvent::Started(2)));
+}
+
+#[test]
+fn test_waves_with_maximum_values() {
+    use rand::SeedableRng;
+    use crate::{geometry::Size, GameRng, GameState};
+
+    let waves = Waves {
+        pause: 0.0,
+        clear_bonus: MAX_BONUS,
+        difficulty_per_wave: MAX_DIFFICULTY_PER_WAVE,
+        endless_growth: MAX_GROWTH,
+        boss_every: 1,
+        boss_health: MAX_BOSS_HEALTH,
+        boss_bonus: MAX_BONUS,
+        waves: vec![Wave { groups: vec![Group { kind: None, count: 1, pattern: SpawnPattern::Random, interval: 0.0 }] }],
+    };
+    assert!(waves.validate().is_ok());
+    assert!(Waves { clear_bonus: MAX_BONUS + 1, ..waves.clone() }.validate().is_err());
+    assert!(Waves { difficulty_per_wave: f32::INFINITY, ..waves.clone() }.validate().is_err());
+
+    // The bonuses grow with every wave and boss, until the score can't go any higher
+    let mut rng = GameRng::seed_from_u64(0);
+    let mut state = GameState::new(Size::new(800.0, 600.0), &mut rng);
+    let mut director = WaveDirector::new(waves);
+    let mut health = 0;
+    while director.wave() < 10_000 {
+        match director.update(1.0, 0, |_| ()) {
+            Some(WaveEvent::Cleared { bonus, .. }) | Some(WaveEvent::BossDefeated { bonus }) => {
+                state.add_score(bonus);
+            }
+            Some(WaveEvent::BossArrived { health: h }) => health = h,
+            _ => (),
+        }
+    }
+    assert_eq!(state.score, u32::MAX);
+    assert_eq!(health, MAX_BOSS_HEALTH * 9_999);
+    assert_eq!(director.definitions().wave(10_000).groups[0].count, MAX_GROUP_COUNT);
+}
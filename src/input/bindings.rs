@@ -0,0 +1,286 @@
+use std::convert::TryFrom;
+use std::fmt;
+
+use ggez::event::{KeyCode, KeyMods};
+use serde::{Deserialize, Serialize};
+
+/// The actions that can be bound to keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    RotateLeft,
+    RotateRight,
+    Boost,
+    Shoot,
+}
+
+impl Action {
+    /// All actions, in the order they are shown to the player
+    pub const ALL: [Action; 4] = [Action::RotateLeft, Action::RotateRight, Action::Boost, Action::Shoot];
+
+    /// The name of the action, as shown to the player
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::RotateLeft => "Rotate left",
+            Action::RotateRight => "Rotate right",
+            Action::Boost => "Boost",
+            Action::Shoot => "Shoot",
+        }
+    }
+}
+
+/// A key, together with the modifiers that must be held for it to trigger an action
+///
+/// Bindings are written as the name of the key, optionally preceded by modifiers: `Space`, `W` or
+/// `Ctrl+Shift+J`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Binding {
+    pub key: KeyCode,
+    pub mods: KeyMods,
+}
+
+impl Binding {
+    /// Returns a binding for the given key, without modifiers
+    pub fn key(key: KeyCode) -> Binding {
+        Binding { key, mods: KeyMods::NONE }
+    }
+
+    /// Whether this binding is triggered by the given key, when pressed along with `mods`
+    ///
+    /// Extra modifiers are allowed, so holding a modifier that is bound to another action doesn't
+    /// get in the way. When several bindings match, `Bindings::action_for_press` picks the most
+    /// specific one.
+    pub fn matches(&self, key: KeyCode, mods: KeyMods) -> bool {
+        self.key == key && mods.contains(self.mods)
+    }
+
+    /// Whether a single key press can match both bindings, like `J` and `Ctrl+J`
+    pub fn overlaps(&self, other: &Binding) -> bool {
+        self.key == other.key && (self.mods.contains(other.mods) || other.mods.contains(self.mods))
+    }
+}
+
+const MODIFIER_NAMES: &[(KeyMods, &str)] = &[
+    (KeyMods::CTRL, "Ctrl"),
+    (KeyMods::ALT, "Alt"),
+    (KeyMods::SHIFT, "Shift"),
+    (KeyMods::LOGO, "Logo"),
+];
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for &(modifier, name) in MODIFIER_NAMES {
+            if self.mods.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        write!(f, "{}", key_name(self.key))
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(text: String) -> Result<Binding, String> {
+        let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+        let key = parts.pop().unwrap();
+        let key = key_by_name(key).ok_or_else(|| format!("unknown key {:?} in binding {:?}", key, text))?;
+
+        let mut mods = KeyMods::NONE;
+        for part in parts {
+            let &(modifier, _) = MODIFIER_NAMES
+                .iter()
+                .find(|(_, name)| name.eq_ignore_ascii_case(part))
+                .ok_or_else(|| format!("unknown modifier {:?} in binding {:?}", part, text))?;
+            mods |= modifier;
+        }
+
+        Ok(Binding { key, mods })
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> String {
+        binding.to_string()
+    }
+}
+
+/// The keys bound to each action
+///
+/// Any number of keys can be bound to an action, but a key can only be bound to one action (with
+/// modifiers that don't overlap, see `Binding::overlaps`)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Bindings {
+    pub rotate_left: Vec<Binding>,
+    pub rotate_right: Vec<Binding>,
+    pub boost: Vec<Binding>,
+    pub shoot: Vec<Binding>,
+}
+
+impl Default for Bindings {
+    fn default() -> Bindings {
+        Bindings {
+            rotate_left: vec![Binding::key(KeyCode::Left)],
+            rotate_right: vec![Binding::key(KeyCode::Right)],
+            boost: vec![Binding::key(KeyCode::Up)],
+            shoot: vec![Binding::key(KeyCode::Space)],
+        }
+    }
+}
+
+impl Bindings {
+    /// Returns the keys bound to the given action
+    pub fn get(&self, action: Action) -> &[Binding] {
+        match action {
+            Action::RotateLeft => &self.rotate_left,
+            Action::RotateRight => &self.rotate_right,
+            Action::Boost => &self.boost,
+            Action::Shoot => &self.shoot,
+        }
+    }
+
+    /// Returns a mutable reference to the keys bound to the given action
+    pub fn get_mut(&mut self, action: Action) -> &mut Vec<Binding> {
+        match action {
+            Action::RotateLeft => &mut self.rotate_left,
+            Action::RotateRight => &mut self.rotate_right,
+            Action::Boost => &mut self.boost,
+            Action::Shoot => &mut self.shoot,
+        }
+    }
+
+    /// Returns the action triggered by pressing `key` along with `mods`, if any
+    ///
+    /// Of the bindings that match the press, the one with the most modifiers wins, so that `J` and
+    /// `Ctrl+J` never trigger at the same time
+    pub fn action_for_press(&self, key: KeyCode, mods: KeyMods) -> Option<Action> {
+        let mut best: Option<(Action, u32)> = None;
+        for &action in &Action::ALL {
+            for binding in self.get(action).iter().filter(|b| b.matches(key, mods)) {
+                let specificity = binding.mods.bits().count_ones();
+                if best.is_none_or(|(_, most)| specificity > most) {
+                    best = Some((action, specificity));
+                }
+            }
+        }
+        best.map(|(action, _)| action)
+    }
+
+    /// Returns a binding that overlaps with the given one, and the action it is bound to, if any
+    pub fn conflict(&self, binding: Binding) -> Option<(Action, Binding)> {
+        Action::ALL.iter().find_map(|&action| {
+            self.get(action).iter().find(|b| b.overlaps(&binding)).map(|&b| (action, b))
+        })
+    }
+
+    /// Checks that no key press can match the bindings of two different actions
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, &action) in Action::ALL.iter().enumerate() {
+            for &other in &Action::ALL[i + 1..] {
+                for binding in self.get(action) {
+                    if let Some(clash) = self.get(other).iter().find(|b| b.overlaps(binding)) {
+                        return Err(format!(
+                            "{} (bound to {:?}) overlaps with {} (bound to {:?})",
+                            binding,
+                            action.name(),
+                            clash,
+                            other.name(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether the key is one of the modifiers (which can be bound on their own too)
+pub fn is_modifier(key: KeyCode) -> bool {
+    matches!(
+        key,
+        KeyCode::LShift
+            | KeyCode::RShift
+            | KeyCode::LControl
+            | KeyCode::RControl
+            | KeyCode::LAlt
+            | KeyCode::RAlt
+            | KeyCode::LWin
+            | KeyCode::RWin
+    )
+}
+
+// Defines `KEYS`, a table from the keys that can be bound to their names, which are the same as
+// the names of the variants of `KeyCode` (Escape is missing on purpose, it's used to leave the
+// rebinding screen)
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        const KEYS: &[(KeyCode, &str)] = &[$((KeyCode::$key, stringify!($key))),*];
+    };
+}
+
+key_names! {
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9, Key0,
+    F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12,
+    Left, Up, Right, Down,
+    Space, Return, Back, Tab, Insert, Delete, Home, End, PageUp, PageDown,
+    LShift, RShift, LControl, RControl, LAlt, RAlt, LWin, RWin,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    NumpadAdd, NumpadSubtract, NumpadMultiply, NumpadDivide, NumpadDecimal, NumpadEnter,
+    Apostrophe, Backslash, Comma, Equals, Grave, LBracket, Minus, Period, RBracket, Semicolon,
+    Slash,
+}
+
+/// Whether the key can be bound to an action
+pub fn is_bindable(key: KeyCode) -> bool {
+    KEYS.iter().any(|&(k, _)| k == key)
+}
+
+/// Returns the name of a key, as written in bindings
+fn key_name(key: KeyCode) -> String {
+    KEYS.iter()
+        .find(|&&(k, _)| k == key)
+        .map(|&(_, name)| name.to_string())
+        .unwrap_or_else(|| format!("{:?}", key))
+}
+
+/// Returns the key with the given name (ignoring case), if it can be bound
+fn key_by_name(name: &str) -> Option<KeyCode> {
+    KEYS.iter()
+        .find(|(_, n)| n.eq_ignore_ascii_case(name))
+        .map(|&(key, _)| key)
+}
+
+#[test]
+fn test_bindings() {
+    let binding = Binding::try_from("ctrl+shift+J".to_string()).unwrap();
+    assert_eq!(binding, Binding { key: KeyCode::J, mods: KeyMods::CTRL | KeyMods::SHIFT });
+    assert_eq!(binding.to_string(), "Ctrl+Shift+J");
+    assert!(binding.matches(KeyCode::J, KeyMods::CTRL | KeyMods::SHIFT | KeyMods::ALT));
+    assert!(!binding.matches(KeyCode::J, KeyMods::CTRL));
+    assert!(Binding::try_from("Hyper+J".to_string()).is_err());
+    assert!(Binding::try_from("Escape".to_string()).is_err());
+
+    let mut bindings = Bindings::default();
+    assert_eq!(bindings.validate(), Ok(()));
+    bindings.shoot.push(Binding::key(KeyCode::Left));
+    assert_eq!(bindings.conflict(Binding::key(KeyCode::Left)), Some((Action::RotateLeft, Binding::key(KeyCode::Left))));
+    assert!(bindings.validate().is_err());
+
+    // A key with modifiers overlaps with the same key without them, since both match `Ctrl+J`
+    let ctrl_j = Binding { key: KeyCode::J, mods: KeyMods::CTRL };
+    let mut bindings = Bindings::default();
+    bindings.shoot.push(Binding::key(KeyCode::J));
+    assert_eq!(bindings.conflict(ctrl_j), Some((Action::Shoot, Binding::key(KeyCode::J))));
+    assert_eq!(bindings.conflict(Binding { key: KeyCode::K, mods: KeyMods::CTRL }), None);
+    bindings.boost.push(ctrl_j);
+    assert!(bindings.validate().is_err());
+
+    // Even so, a press only triggers the most specific of the bindings it matches
+    assert_eq!(bindings.action_for_press(KeyCode::J, KeyMods::CTRL), Some(Action::Boost));
+    assert_eq!(bindings.action_for_press(KeyCode::J, KeyMods::SHIFT), Some(Action::Shoot));
+    assert_eq!(bindings.action_for_press(KeyCode::Space, KeyMods::SHIFT), Some(Action::Shoot));
+    assert_eq!(bindings.action_for_press(KeyCode::K, KeyMods::NONE), None);
+}
@@ -0,0 +1,193 @@
+mod bindings;
+mod rebinding;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use ggez::event::{Axis, Button, KeyCode, KeyMods, MouseButton};
+use geometry::Point;
+use rocket_core::{controllers::TIMESTEP, Actions, GameState};
+
+pub use self::bindings::{Action, Bindings};
+pub use self::rebinding::RebindingScreen;
+
+/// Stick positions closer to the center than this are ignored, since sticks rarely rest at 0
+const STICK_DEAD_ZONE: f32 = 0.15;
+
+/// The ways to steer the rocket
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ControlScheme {
+    /// Rotate with the keyboard (or a gamepad's stick)
+    #[default]
+    Keyboard,
+    /// The rocket turns towards the mouse cursor, the left button shoots and the right one boosts
+    Mouse,
+}
+
+impl FromStr for ControlScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ControlScheme, String> {
+        match s {
+            "keyboard" => Ok(ControlScheme::Keyboard),
+            "mouse" => Ok(ControlScheme::Mouse),
+            _ => Err(format!("unknown controls {:?}, expected keyboard or mouse", s)),
+        }
+    }
+}
+
+/// The state of the mouse, as far as the game is concerned
+#[derive(Default)]
+struct Mouse {
+    // The position of the cursor, once it has entered the window
+    position: Option<Point>,
+    left_button: bool,
+    right_button: bool,
+}
+
+/// The state of the gamepad, as far as the game is concerned
+#[derive(Default)]
+struct Gamepad {
+    // The position of the left stick along the X axis, from -1 (left) to 1 (right)
+    stick_x: f32,
+    // How far the left trigger is pressed, from 0 to 1
+    left_trigger: f32,
+    // Whether the right trigger is pressed
+    right_trigger: bool,
+}
+
+/// Combines the input of the keyboard, gamepad and mouse into `Actions`
+#[derive(Default)]
+pub struct InputController {
+    // The actions triggered by buttons and sticks, aiming with the mouse comes on top of them
+    actions: Actions,
+    scheme: ControlScheme,
+    bindings: Bindings,
+    // The keys that are currently held, along with the modifiers that were held when pressing them
+    held_keys: HashMap<KeyCode, KeyMods>,
+    gamepad: Gamepad,
+    mouse: Mouse,
+}
+
+impl InputController {
+    /// Create a new `InputController` that uses the given control scheme and bindings
+    pub fn new(scheme: ControlScheme, bindings: Bindings) -> InputController {
+        InputController { scheme, bindings, ..InputController::default() }
+    }
+
+    /// Returns the actions for the next update of the game
+    ///
+    /// With the mouse control scheme, the rocket turns towards the cursor, as fast as it can
+    pub fn actions(&self, state: &GameState) -> Actions {
+        let mut actions = self.actions;
+        if let (ControlScheme::Mouse, Some(cursor)) = (self.scheme, self.mouse.position) {
+            if actions.rotation == 0.0 && !state.world.player.is_dead {
+                let max_turn = state.config.player.rotate_speed * TIMESTEP.as_secs_f32();
+                actions.rotation = state.world.player.rotation_towards(cursor, state.world.size, max_turn);
+            }
+        }
+        actions
+    }
+
+    /// Returns the keys bound to each action
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    /// Replaces the keys bound to each action
+    pub fn set_bindings(&mut self, bindings: Bindings) {
+        self.bindings = bindings;
+        self.update_actions();
+    }
+
+    /// Processes a key press
+    pub fn key_press(&mut self, keycode: KeyCode, keymod: KeyMods) {
+        self.held_keys.insert(keycode, keymod);
+        self.update_actions();
+    }
+
+    /// Processes a key release
+    pub fn key_release(&mut self, keycode: KeyCode, _keymod: KeyMods) {
+        self.held_keys.remove(&keycode);
+        self.update_actions();
+    }
+
+    /// Processes a gamepad button press or release
+    ///
+    /// The left trigger boosts and the right trigger shoots. On gamepads where the triggers are
+    /// analog they are also reported as axes, see `gamepad_axis`.
+    pub fn gamepad_button(&mut self, button: Button, pressed: bool) {
+        match button {
+            Button::LeftTrigger2 => self.gamepad.left_trigger = if pressed { 1.0 } else { 0.0 },
+            Button::RightTrigger2 => self.gamepad.right_trigger = pressed,
+            _ => return,
+        }
+        self.update_actions();
+    }
+
+    /// Processes the movement of a gamepad axis
+    ///
+    /// The X axis of the left stick rotates the rocket, proportionally to how far it's pushed
+    pub fn gamepad_axis(&mut self, axis: Axis, value: f32) {
+        match axis {
+            Axis::LeftStickX => self.gamepad.stick_x = apply_dead_zone(value),
+            Axis::LeftZ => self.gamepad.left_trigger = value.clamp(0.0, 1.0),
+            Axis::RightZ => self.gamepad.right_trigger = value > 0.5,
+            _ => return,
+        }
+        self.update_actions();
+    }
+
+    /// Processes the movement of the mouse cursor, to the given position in the world
+    pub fn mouse_motion(&mut self, position: Point) {
+        self.mouse.position = Some(position);
+    }
+
+    /// Processes a mouse button press or release
+    pub fn mouse_button(&mut self, button: MouseButton, pressed: bool) {
+        match button {
+            MouseButton::Left => self.mouse.left_button = pressed,
+            MouseButton::Right => self.mouse.right_button = pressed,
+            _ => return,
+        }
+        self.update_actions();
+    }
+
+    /// Triggers each action if a held key triggers it (see `Bindings::action_for_press`), or
+    /// through the gamepad or mouse
+    ///
+    /// When both the keyboard and the gamepad rotate the rocket, the keyboard wins. The mouse
+    /// buttons are only used with the mouse control scheme.
+    fn update_actions(&mut self) {
+        let is_held = |action| {
+            self.held_keys.iter().any(|(&key, &mods)| self.bindings.action_for_press(key, mods) == Some(action))
+        };
+
+        let keyboard = Actions::from_buttons(
+            is_held(Action::RotateLeft),
+            is_held(Action::RotateRight),
+            is_held(Action::Boost),
+            is_held(Action::Shoot),
+        );
+
+        let (mouse_boost, mouse_shoot) = match self.scheme {
+            ControlScheme::Keyboard => (false, false),
+            ControlScheme::Mouse => (self.mouse.right_button, self.mouse.left_button),
+        };
+
+        self.actions = Actions {
+            rotation: if keyboard.rotation != 0.0 { keyboard.rotation } else { self.gamepad.stick_x },
+            thrust: keyboard.thrust.max(self.gamepad.left_trigger).max(if mouse_boost { 1.0 } else { 0.0 }),
+            shoot: keyboard.shoot || self.gamepad.right_trigger || mouse_shoot,
+        };
+    }
+}
+
+/// Ignores small stick movements, and rescales the rest so the stick still reaches -1 and 1
+fn apply_dead_zone(value: f32) -> f32 {
+    if value.abs() < STICK_DEAD_ZONE {
+        0.0
+    } else {
+        value.signum() * (value.abs() - STICK_DEAD_ZONE) / (1.0 - STICK_DEAD_ZONE)
+    }
+}
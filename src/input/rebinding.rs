@@ -0,0 +1,118 @@
+use ggez::event::{KeyCode, KeyMods};
+
+use super::bindings::{self, Action, Binding, Bindings};
+
+/// What the rebinding screen is waiting for
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// The player is choosing an action
+    Browsing,
+    /// The player is pressing the key to bind to the selected action
+    ///
+    /// If the player presses a modifier, we wait to see whether it's part of a combination (like
+    /// `Ctrl+J`) or whether it's released, in which case the modifier itself is bound
+    Capturing { modifier: Option<KeyCode> },
+}
+
+/// A screen where the player can change the keys bound to each action
+///
+/// Keys are added to the selected action one by one. A key that is already bound to another
+/// action is refused, so the bindings never conflict, and the player can't leave while an action
+/// has no keys, so every action stays playable.
+pub struct RebindingScreen {
+    bindings: Bindings,
+    selected: usize,
+    state: State,
+    status: Option<String>,
+}
+
+impl RebindingScreen {
+    /// Returns a rebinding screen that starts from the given bindings
+    pub fn new(bindings: Bindings) -> RebindingScreen {
+        RebindingScreen {
+            bindings,
+            selected: 0,
+            state: State::Browsing,
+            status: None,
+        }
+    }
+
+    /// Returns the bindings, as edited so far
+    pub fn bindings(&self) -> &Bindings {
+        &self.bindings
+    }
+
+    /// Returns the selected action
+    pub fn selected(&self) -> Action {
+        Action::ALL[self.selected]
+    }
+
+    /// Whether the screen is waiting for a key to bind to the selected action
+    pub fn is_capturing(&self) -> bool {
+        self.state != State::Browsing
+    }
+
+    /// Returns a message for the player about the last change, e.g. when a key was refused
+    pub fn status(&self) -> Option<&str> {
+        self.status.as_deref()
+    }
+
+    /// Processes a key press, returning true when the player is done (which is refused while an
+    /// action has no keys)
+    pub fn key_press(&mut self, key: KeyCode, mods: KeyMods) -> bool {
+        match self.state {
+            State::Browsing => match key {
+                KeyCode::Up => self.selected = (self.selected + Action::ALL.len() - 1) % Action::ALL.len(),
+                KeyCode::Down => self.selected = (self.selected + 1) % Action::ALL.len(),
+                KeyCode::Return | KeyCode::NumpadEnter => {
+                    self.state = State::Capturing { modifier: None };
+                    self.status = None;
+                }
+                KeyCode::Back | KeyCode::Delete => {
+                    self.bindings.get_mut(self.selected()).clear();
+                    self.status = None;
+                }
+                KeyCode::Escape => match Action::ALL.iter().find(|&&action| self.bindings.get(action).is_empty()) {
+                    Some(action) => self.status = Some(format!("{} has no keys, add one before leaving", action.name())),
+                    None => return true,
+                },
+                _ => (),
+            },
+            State::Capturing { .. } if key == KeyCode::Escape => self.state = State::Browsing,
+            State::Capturing { .. } if bindings::is_modifier(key) => {
+                self.state = State::Capturing { modifier: Some(key) };
+            }
+            State::Capturing { .. } => self.bind(Binding { key, mods }),
+        }
+
+        false
+    }
+
+    /// Processes a key release
+    pub fn key_release(&mut self, key: KeyCode) {
+        if self.state == (State::Capturing { modifier: Some(key) }) {
+            self.bind(Binding::key(key));
+        }
+    }
+
+    /// Binds a key to the selected action, unless it's bound to another action already
+    fn bind(&mut self, binding: Binding) {
+        self.state = State::Browsing;
+        let action = self.selected();
+        self.status = match self.bindings.conflict(binding) {
+            _ if !bindings::is_bindable(binding.key) => Some(format!("{:?} can't be bound", binding.key)),
+            Some((other, existing)) if other == action => {
+                Some(format!("{} is already bound to {}", existing, other.name()))
+            }
+            Some((other, existing)) => Some(format!(
+                "{} is already bound to {}, clear it there first",
+                existing,
+                other.name(),
+            )),
+            None => {
+                self.bindings.get_mut(action).push(binding);
+                None
+            }
+        };
+    }
+}
@@ -1,24 +1,31 @@
 //! A 2D toy game written in Rust, using the ggez library.
 #![deny(missing_docs)]
 
-extern crate geometry;
-extern crate geometry_derive;
-
-mod controllers;
+mod input;
+mod storage;
 mod view;
-mod game_state;
-mod models;
-mod util;
 
-use ggez::event::{self, KeyCode, KeyMods};
+use std::path::PathBuf;
+use std::process;
+use std::time::Duration;
+
+use ggez::event::{self, Axis, Button, GamepadId, KeyCode, KeyMods, MouseButton};
 use ggez::{Context, GameResult};
-use rand::prelude::ThreadRng;
+use geometry::{Point, Size};
+use rand::SeedableRng;
+use rocket_core::{
+    bots::{self, BOT_NAMES},
+    config::GameConfig,
+    controllers::{FixedTimestep, TIMESTEP},
+    high_scores::{HighScore, HighScores, MAX_NAME_LENGTH},
+    save::{GameToSave, SavedGame},
+    waves::{WaveDirector, Waves},
+    CollisionsController, Event, GameRng, GameState, Replay, TimeController,
+};
 use structopt::StructOpt;
 
 use crate::{
-    controllers::{CollisionsController, Event, InputController, TimeController},
-    game_state::GameState,
-    geometry::Size,
+    input::{ControlScheme, InputController, RebindingScreen},
     view::Resources,
 };
 
@@ -27,47 +34,175 @@ use crate::{
 pub struct ApplicationState {
     // Keep track of window focus to play/pause the game
     has_focus: bool,
+    // Whether the player paused the game (with the Start button of the gamepad)
+    paused: bool,
     // Resources holds our loaded font, images and sounds
     resources: Resources,
     // The game state contains all information needed to run the game
     game_state: GameState,
     // The time controller modifies the game state as time passes
     time_controller: TimeController,
+    // The fixed timestep splits the time between frames into updates of equal length
+    timestep: FixedTimestep,
     // The input controller keeps track of the actions that are triggered by the player
     input_controller: InputController,
     // The event buffer keeps track of events that trigger sounds, so we can separate
     // sound playing from the game logic
     event_buffer: Vec<Event>,
-    // A source of randomness
-    rng: ThreadRng,
+    // The seed of the current game, which can be passed to `--seed` to replay it
+    seed: u64,
+    // A source of randomness, seeded with `seed` at the start of each game
+    rng: GameRng,
+    // The recording of the current game, and the file to save it to once the game is over
+    recording: Replay,
+    record_path: Option<PathBuf>,
+    // When watching a replay, the actions are taken from it instead of from the keyboard
+    playback: Option<Replay>,
+    // The amount of updates since the start of the current game
+    tick: usize,
+    // How long the player has been alive in the current game
+    survival_time: Duration,
+    // The best scores so far
+    high_scores: HighScores,
+    // When a game ends with a new high score, the entry whose name is being typed by the player
+    pending_high_score: Option<HighScore>,
+    // The screen to change the key bindings, when it's open
+    rebinding: Option<RebindingScreen>,
 }
 
 impl ApplicationState {
-    /// Simply creates a new application state
-    fn new(ctx: &mut Context, game_size: Size) -> GameResult<ApplicationState> {
-        let mut rng = rand::thread_rng();
-        let app_state = ApplicationState {
+    /// Simply creates a new application state around the given game, where the first game will
+    /// use the given seed
+    ///
+    /// If a replay is given it is played right away, and if a record path is given each game is
+    /// saved to it when it's over
+    fn new(
+        ctx: &mut Context,
+        game_state: GameState,
+        input_controller: InputController,
+        seed: u64,
+        playback: Option<Replay>,
+        record_path: Option<PathBuf>,
+    ) -> GameResult<ApplicationState> {
+        let (game_size, config) = (game_state.world.size, game_state.config);
+        let waves = game_state.waves.definitions().clone();
+        let mut app_state = ApplicationState {
             has_focus: true,
+            paused: false,
             resources: Resources::new(ctx),
-            game_state: GameState::new(game_size, &mut rng),
-            time_controller: TimeController::new(),
-            input_controller: InputController::new(),
+            time_controller: TimeController::new(&game_state.config),
+            game_state,
+            timestep: FixedTimestep::new(),
+            input_controller,
             event_buffer: Vec::new(),
-            rng,
+            seed,
+            rng: GameRng::seed_from_u64(seed),
+            recording: Replay::new(seed, game_size, config, waves),
+            record_path,
+            playback,
+            tick: 0,
+            survival_time: Duration::from_secs(0),
+            high_scores: storage::load_high_scores(ctx),
+            pending_high_score: None,
+            rebinding: None,
         };
+
+        if app_state.playback.is_some() {
+            app_state.reset();
+        }
+
         Ok(app_state)
     }
 
     /// This will be called when the game needs to be reset
     fn reset(&mut self) {
         // Reset time controller
-        self.time_controller.reset();
+        self.time_controller.reset(&self.game_state.config);
 
-        // Reset game state
+        // Reset game state, making sure that it only depends on the seed
+        if let Some(ref replay) = self.playback {
+            self.seed = replay.seed;
+        }
+        self.rng = GameRng::seed_from_u64(self.seed);
         self.game_state.reset(&mut self.rng);
 
+        // Start recording the new game
+        let waves = self.game_state.waves.definitions().clone();
+        self.recording = Replay::new(self.seed, self.game_state.world.size, self.game_state.config, waves);
+        self.tick = 0;
+        self.survival_time = Duration::from_secs(0);
+
         self.event_buffer.push(Event::GameStart);
     }
+
+    /// Continues a saved game
+    fn resume(&mut self, saved: SavedGame) {
+        self.seed = saved.seed;
+        self.game_state = saved.state;
+        self.time_controller = saved.time_controller;
+        self.rng = saved.rng;
+        self.tick = saved.recording.actions().len();
+        self.survival_time = self.time_controller.current_time();
+        self.recording = saved.recording;
+    }
+
+    /// Saves the current game, so it can be resumed later
+    fn save(&self, ctx: &Context) -> GameResult<()> {
+        let game = GameToSave::new(self.seed, &self.game_state, &self.time_controller, &self.rng, &self.recording);
+        storage::save_game(ctx, &game)
+    }
+
+    /// Called when the game is over
+    fn game_over(&mut self) {
+        // Print the seed so the run can be shared, and save the recording
+        println!("Game over! Score: {}, seed: {}", self.game_state.score, self.seed);
+        if let Some(ref path) = self.record_path {
+            match storage::save_replay(&self.recording, path) {
+                Ok(()) => println!("Replay saved to {}", path.display()),
+                Err(e) => eprintln!("Could not save replay to {}: {}", path.display(), e),
+            }
+        }
+
+        // Let the player type their name if they made it into the high score table (except when
+        // watching a replay, that score has already been set)
+        if self.playback.is_none() && self.high_scores.qualifies(self.game_state.score) {
+            self.pending_high_score = Some(HighScore {
+                name: String::new(),
+                score: self.game_state.score,
+                date: chrono::Local::now().format("%Y-%m-%d").to_string(),
+                seed: self.seed,
+                survival_time: self.survival_time.as_secs_f32(),
+            });
+        }
+
+        // The next game gets a new seed
+        self.seed = rand::random();
+    }
+
+    /// Closes the rebinding screen, starting to use (and saving) the new bindings
+    fn close_rebinding_screen(&mut self, ctx: &Context) {
+        if let Some(rebinding) = self.rebinding.take() {
+            if let Err(e) = storage::save_bindings(ctx, rebinding.bindings()) {
+                eprintln!("Could not save the controls: {}", e);
+            }
+            self.input_controller.set_bindings(rebinding.bindings().clone());
+        }
+    }
+
+    /// Adds the pending high score to the table, once the player has typed their name
+    fn submit_high_score(&mut self, ctx: &Context) {
+        if let Some(mut high_score) = self.pending_high_score.take() {
+            high_score.name = high_score.name.trim().to_string();
+            if high_score.name.is_empty() {
+                high_score.name = "Anonymous".to_string();
+            }
+
+            self.high_scores.insert(high_score);
+            if let Err(e) = storage::save_high_scores(ctx, &self.high_scores) {
+                eprintln!("Could not save the high scores: {}", e);
+            }
+        }
+    }
 }
 
 // We implement `ggez::event::EventHandler` trait on our application state - this is where we can
@@ -75,22 +210,44 @@ impl ApplicationState {
 impl event::EventHandler for ApplicationState {
     // This is called each time the game loop updates so we can update the game state
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
-        // Pause the game if the window has no focus
-        if !self.has_focus {
+        // Pause the game if the window has no focus, or if the player paused it
+        if !self.has_focus || self.paused {
             return Ok(())
         }
 
-        // Update game state, and check for collisions
-        let duration = ggez::timer::delta(ctx);
-        self.time_controller.update_seconds(
-            duration,
-            self.input_controller.actions(),
-            &mut self.game_state,
-            &mut self.event_buffer,
-            &mut self.rng
-        );
+        // Keep track of whether a game is running, to notice when it ends
+        let was_playing = self.game_state.message.is_none();
+
+        // Update game state, and check for collisions, as many times as needed to catch up with
+        // the time that has passed since the last frame
+        let ticks = self.timestep.advance(ggez::timer::delta(ctx));
+        for _ in 0..ticks {
+            // Replays are played until they run out of actions, after which nothing is pressed
+            let actions = match self.playback {
+                Some(ref replay) => replay.actions().get(self.tick).copied().unwrap_or_default(),
+                None => self.input_controller.actions(&self.game_state),
+            };
+            self.recording.record(&actions);
+            self.tick += 1;
 
-        CollisionsController::handle_collisions(&mut self.game_state, &mut self.time_controller, &mut self.event_buffer);
+            self.time_controller.update_seconds(
+                TIMESTEP,
+                &actions,
+                &mut self.game_state,
+                &mut self.event_buffer,
+                &mut self.rng
+            );
+
+            CollisionsController::handle_collisions(&mut self.game_state, &mut self.time_controller, &mut self.event_buffer);
+
+            if !self.game_state.world.player.is_dead {
+                self.survival_time = self.time_controller.current_time();
+            }
+        }
+
+        if was_playing && self.game_state.message.is_some() {
+            self.game_over();
+        }
 
         Ok(())
     }
@@ -102,22 +259,107 @@ impl event::EventHandler for ApplicationState {
     }
 
     // Listen for keyboard events
-    fn key_down_event(&mut self, _ctx: &mut Context, keycode: KeyCode, keymod: KeyMods, _repeat: bool) {
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, keymod: KeyMods, _repeat: bool) {
+        // While the player types their name, the keyboard is only used for that
+        if let Some(ref mut high_score) = self.pending_high_score {
+            match keycode {
+                KeyCode::Back => {
+                    high_score.name.pop();
+                }
+                KeyCode::Return | KeyCode::NumpadEnter => self.submit_high_score(ctx),
+                _ => (),
+            }
+            return;
+        }
+
+        // While the rebinding screen is open, the keyboard is only used for that
+        if let Some(ref mut rebinding) = self.rebinding {
+            if rebinding.key_press(keycode, keymod) {
+                self.close_rebinding_screen(ctx);
+            }
+            return;
+        }
+
+        // The rebinding screen can be opened while waiting for the player to start a game
+        if self.game_state.message.is_some() && keycode == KeyCode::F1 {
+            self.rebinding = Some(RebindingScreen::new(self.input_controller.bindings().clone()));
+            return;
+        }
+
         // If we're displaying a message (waiting for user input) then hide it and reset the game
-        if let Some(_) = self.game_state.message {
+        if self.game_state.message.is_some() {
             self.reset();
         }
         self.input_controller.key_press(keycode, keymod);
     }
 
     fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, keymod: KeyMods) {
+        if let Some(ref mut rebinding) = self.rebinding {
+            rebinding.key_release(keycode);
+        }
         self.input_controller.key_release(keycode, keymod);
     }
 
+    // Listen for typed characters, used to enter the player's name
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        if let Some(ref mut high_score) = self.pending_high_score {
+            if !character.is_control() && high_score.name.chars().count() < MAX_NAME_LENGTH {
+                high_score.name.push(character);
+            }
+        }
+    }
+
+    // Listen for the mouse, which is used to aim with the mouse control scheme
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        self.input_controller.mouse_motion(Point::new(x, y));
+    }
+
+    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
+        self.input_controller.mouse_button(button, true);
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
+        self.input_controller.mouse_button(button, false);
+    }
+
+    // Listen for gamepad buttons, Start pauses the game and resumes it
+    fn gamepad_button_down_event(&mut self, _ctx: &mut Context, button: Button, _id: GamepadId) {
+        // Start also starts a new game, unless the keyboard is being used for a menu
+        if button == Button::Start {
+            if self.game_state.message.is_none() {
+                self.paused = !self.paused;
+            } else if self.pending_high_score.is_none() && self.rebinding.is_none() {
+                self.reset();
+            }
+        }
+        self.input_controller.gamepad_button(button, true);
+    }
+
+    fn gamepad_button_up_event(&mut self, _ctx: &mut Context, button: Button, _id: GamepadId) {
+        self.input_controller.gamepad_button(button, false);
+    }
+
+    fn gamepad_axis_event(&mut self, _ctx: &mut Context, axis: Axis, value: f32, _id: GamepadId) {
+        self.input_controller.gamepad_axis(axis, value);
+    }
+
     // Listen for window focus to pause the game's execution
     fn focus_event(&mut self, _ctx: &mut Context, has_focus: bool) {
         self.has_focus = has_focus;
     }
+
+    // Save the game in progress (if any) when the window is closed, so it can be resumed
+    fn quit_event(&mut self, ctx: &mut Context) -> bool {
+        if self.game_state.message.is_none() && self.playback.is_none() {
+            match self.save(ctx) {
+                Ok(()) => println!("Game saved, continue it with --resume"),
+                Err(e) => eprintln!("Could not save the game: {}", e),
+            }
+        }
+
+        // Returning false lets the game quit
+        false
+    }
 }
 
 #[derive(StructOpt, Debug)]
@@ -130,16 +372,145 @@ struct Opt {
     /// Window height
     #[structopt(long = "height", default_value = "576")]
     height: usize,
+
+    /// Seed for the first game (a random one is used if omitted)
+    #[structopt(long = "seed")]
+    seed: Option<u64>,
+
+    /// How to steer the rocket: keyboard, or mouse (the rocket turns towards the cursor, the left
+    /// button shoots and the right one boosts)
+    #[structopt(long = "controls", default_value = "keyboard")]
+    controls: ControlScheme,
+
+    /// Tune the game with the values in this TOML file
+    #[structopt(long = "config", parse(from_os_str))]
+    config: Option<PathBuf>,
+
+    /// Play the waves of enemies defined in this TOML file
+    #[structopt(long = "waves", parse(from_os_str))]
+    waves: Option<PathBuf>,
+
+    /// Save a replay of each game to this file when the game is over
+    #[structopt(long = "record", parse(from_os_str))]
+    record: Option<PathBuf>,
+
+    /// Watch the replay stored in this file instead of playing
+    #[structopt(long = "replay", parse(from_os_str))]
+    replay: Option<PathBuf>,
+
+    /// Continue the game that was saved when the window was last closed
+    #[structopt(long = "resume")]
+    resume: bool,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(StructOpt, Debug)]
+enum Command {
+    /// Let a bot play a game without opening a window, and print a JSON summary of the game
+    Simulate {
+        /// Maximum duration of the game, in seconds
+        #[structopt(long = "seconds", default_value = "600")]
+        seconds: u64,
+
+        /// Seed of the game (a random one is used if omitted)
+        #[structopt(long = "seed")]
+        seed: Option<u64>,
+
+        /// The bot that plays the game: idle, spinner, random or hunter
+        #[structopt(long = "policy", default_value = "hunter")]
+        policy: String,
+    },
+}
+
+/// Runs a headless game and prints its summary to stdout
+fn simulate(game_size: Size, config: GameConfig, waves: Waves, seconds: u64, seed: Option<u64>, policy: &str) {
+    let seed = seed.unwrap_or_else(rand::random);
+    let mut bot = bots::bot_by_name(policy, seed).unwrap_or_else(|| {
+        eprintln!("Unknown policy {:?}, expected one of: {}", policy, BOT_NAMES.join(", "));
+        process::exit(1);
+    });
+
+    let duration = Duration::from_secs(seconds);
+    let summary = rocket_core::simulation::simulate(seed, game_size, config, waves, duration, &mut *bot);
+    println!("{}", serde_json::to_string_pretty(&summary).unwrap());
 }
 
 fn main() {
     let opt = Opt::from_args();
-    let game_size = Size::new(opt.width as f32, opt.height as f32);
+    let (config, config_bindings) = match opt.config {
+        Some(ref path) => storage::load_config(path).unwrap_or_else(|e| {
+            eprintln!("Could not load config {}: {}", path.display(), e);
+            process::exit(1);
+        }),
+        None => (GameConfig::default(), None),
+    };
+    let waves = match opt.waves {
+        Some(ref path) => storage::load_waves(path).unwrap_or_else(|e| {
+            eprintln!("Could not load waves {}: {}", path.display(), e);
+            process::exit(1);
+        }),
+        None => Waves::default(),
+    };
+
+    if let Some(Command::Simulate { seconds, seed, ref policy }) = opt.command {
+        let game_size = Size::new(opt.width as f32, opt.height as f32);
+        simulate(game_size, config, waves, seconds, seed, policy);
+        return;
+    }
+
+    let playback = opt.replay.as_ref().map(|path| {
+        storage::load_replay(path).unwrap_or_else(|e| {
+            eprintln!("Could not load replay {}: {}", path.display(), e);
+            process::exit(1);
+        })
+    });
+
+    // A replay brings its own world size, seed, config and waves
+    let (game_size, seed, config, waves) = match playback {
+        Some(ref replay) => {
+            if opt.config.is_some() && replay.config != config {
+                eprintln!("The replay was recorded with a different config, playing it with its own");
+            }
+            if opt.waves.is_some() && replay.waves != waves {
+                eprintln!("The replay was recorded with different waves, playing it with its own");
+            }
+            (replay.size, replay.seed, replay.config, replay.waves.clone())
+        }
+        None => (
+            Size::new(opt.width as f32, opt.height as f32),
+            opt.seed.unwrap_or_else(rand::random),
+            config,
+            waves,
+        ),
+    };
 
     // Create the rendering context and set the background color to black
     let (mut ctx, event_loop) = view::init_rendering_ctx(game_size).unwrap();
 
+    // The controls from the config file take precedence over the ones chosen in the game
+    let bindings = config_bindings.unwrap_or_else(|| {
+        storage::load_bindings(&ctx).unwrap_or_else(|e| {
+            eprintln!("Could not load the controls, using the default ones: {}", e);
+            None
+        }).unwrap_or_default()
+    });
+
     // Load the application state and start the event loop
-    let state = ApplicationState::new(&mut ctx, game_size).unwrap();
+    let input_controller = InputController::new(opt.controls, bindings);
+    let mut game_state = GameState::with_config(game_size, config, &mut GameRng::seed_from_u64(seed));
+    game_state.waves = WaveDirector::new(waves);
+    let mut state = ApplicationState::new(&mut ctx, game_state, input_controller, seed, playback, opt.record).unwrap();
+    if opt.resume {
+        match storage::load_saved_game(&ctx) {
+            Ok(Some(saved)) => {
+                view::resize_rendering_ctx(&mut ctx, saved.state.world.size).unwrap();
+                state.resume(saved);
+            }
+            Ok(None) => eprintln!("There is no saved game to resume, starting a new one"),
+            Err(e) => eprintln!("Could not resume the saved game, starting a new one: {}", e),
+        }
+    }
     event::run(ctx, event_loop, state);
 }
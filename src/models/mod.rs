@@ -1,17 +0,0 @@
-mod powerup;
-mod bullet;
-mod enemy;
-mod particle;
-mod player;
-mod world;
-mod star;
-mod gun;
-
-pub use self::powerup::{Powerup, PowerupKind};
-pub use self::bullet::Bullet;
-pub use self::enemy::Enemy;
-pub use self::particle::Particle;
-pub use self::player::{POLYGON as PLAYER_POLYGON, Player};
-pub use self::world::World;
-pub use self::star::Star;
-pub use self::gun::Gun;
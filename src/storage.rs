@@ -0,0 +1,148 @@
+//! Reading and writing the files used by the game: configs, waves, replays, saved games, high
+//! scores and key bindings
+//!
+//! Configs, waves and replays are stored wherever the player wants, the other files live in the user's
+//! data directory (through ggez's filesystem)
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use ggez::{filesystem, Context, GameResult};
+use serde::{Deserialize, Serialize};
+use rocket_core::{
+    config::GameConfig,
+    high_scores::HighScores,
+    save::{GameToSave, SavedGame},
+    waves::Waves,
+    Replay,
+};
+
+use crate::input::Bindings;
+
+/// The file where a game in progress is saved when quitting
+const SAVE_FILE: &str = "/savegame.json";
+
+/// The file containing the high score table
+const HIGH_SCORES_FILE: &str = "/highscores.json";
+
+/// The file containing the key bindings chosen in the rebinding screen
+const CONTROLS_FILE: &str = "/controls.toml";
+
+/// The part of a config file that is read by the binary instead of by `rocket_core`
+#[derive(Serialize, Deserialize)]
+struct ControlsSection {
+    controls: Option<Bindings>,
+}
+
+impl ControlsSection {
+    /// Parses the `[controls]` section of a TOML file, if there is one
+    fn from_toml(toml: &str) -> io::Result<Option<Bindings>> {
+        let section: ControlsSection = toml::from_str(toml)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        if let Some(ref bindings) = section.controls {
+            bindings
+                .validate()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        }
+        Ok(section.controls)
+    }
+}
+
+/// Loads the tuning of the game from the given TOML file, along with the key bindings if the file
+/// has a `[controls]` section
+pub fn load_config(path: &Path) -> io::Result<(GameConfig, Option<Bindings>)> {
+    let mut toml = String::new();
+    File::open(path)?.read_to_string(&mut toml)?;
+    let bindings = ControlsSection::from_toml(&toml)?;
+    if bindings.is_some() {
+        // `rocket_core` rejects sections it doesn't know about, so it gets the file without the
+        // key bindings
+        let mut table: toml::value::Table = toml::from_str(&toml).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        table.remove("controls");
+        toml = toml::to_string(&toml::Value::Table(table))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    }
+    Ok((GameConfig::from_toml(&toml)?, bindings))
+}
+
+/// Loads the definitions of the waves of enemies from the given TOML file
+pub fn load_waves(path: &Path) -> io::Result<Waves> {
+    Waves::read_from(BufReader::new(File::open(path)?))
+}
+
+/// Loads the key bindings that were chosen in the rebinding screen, if any
+pub fn load_bindings(ctx: &Context) -> GameResult<Option<Bindings>> {
+    if !filesystem::exists(ctx, CONTROLS_FILE) {
+        return Ok(None);
+    }
+
+    let mut toml = String::new();
+    filesystem::open(ctx, CONTROLS_FILE)?.read_to_string(&mut toml)?;
+    Ok(ControlsSection::from_toml(&toml)?)
+}
+
+/// Saves the key bindings chosen in the rebinding screen
+pub fn save_bindings(ctx: &Context, bindings: &Bindings) -> GameResult<()> {
+    let section = ControlsSection { controls: Some(bindings.clone()) };
+    let toml = toml::to_string(&section).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+    filesystem::create(ctx, CONTROLS_FILE)?.write_all(toml.as_bytes())?;
+    Ok(())
+}
+
+/// Loads a replay from the given file
+pub fn load_replay(path: &Path) -> io::Result<Replay> {
+    Replay::read_from(BufReader::new(File::open(path)?))
+}
+
+/// Saves a replay to the given file
+pub fn save_replay(replay: &Replay, path: &Path) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    replay.write_to(&mut writer)?;
+    writer.flush()
+}
+
+/// Loads the saved game, if there is one, and removes it so it's only resumed once
+pub fn load_saved_game(ctx: &Context) -> GameResult<Option<SavedGame>> {
+    if !filesystem::exists(ctx, SAVE_FILE) {
+        return Ok(None);
+    }
+
+    let saved = SavedGame::read_from(BufReader::new(filesystem::open(ctx, SAVE_FILE)?))?;
+    filesystem::delete(ctx, SAVE_FILE)?;
+    Ok(Some(saved))
+}
+
+/// Saves a game in progress, so it can be resumed later
+pub fn save_game(ctx: &Context, game: &GameToSave) -> GameResult<()> {
+    let mut writer = BufWriter::new(filesystem::create(ctx, SAVE_FILE)?);
+    game.write_to(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Loads the high score table
+///
+/// If the file is missing we start with an empty table. If it can't be read we also start with an
+/// empty table, which will replace the broken file once a new high score is set.
+pub fn load_high_scores(ctx: &Context) -> HighScores {
+    if !filesystem::exists(ctx, HIGH_SCORES_FILE) {
+        return HighScores::new();
+    }
+
+    let high_scores = filesystem::open(ctx, HIGH_SCORES_FILE)
+        .map_err(|e| e.to_string())
+        .and_then(|file| HighScores::read_from(BufReader::new(file)).map_err(|e| e.to_string()));
+    high_scores.unwrap_or_else(|e| {
+        eprintln!("Could not read the high scores, starting with an empty table: {}", e);
+        HighScores::new()
+    })
+}
+
+/// Saves the high score table
+pub fn save_high_scores(ctx: &Context, high_scores: &HighScores) -> GameResult<()> {
+    let mut writer = BufWriter::new(filesystem::create(ctx, HIGH_SCORES_FILE)?);
+    high_scores.write_to(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
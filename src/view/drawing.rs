@@ -54,7 +54,42 @@ pub mod color {
     pub const STAR: Color = GREY;
     pub const PARTICLE: Color = ORANGE;
     pub const BULLET: Color = CYAN;
+    pub const HOMING_BULLET: Color = GREEN;
     pub const ENEMY: Color = YELLOW;
+    pub const ENEMY_TANK: Color = ORANGE;
+    pub const ENEMY_DASHER: Color = Color {
+        r: 1.0,
+        g: 0.0,
+        b: 1.0,
+        a: 1.0,
+    };
+    pub const ENEMY_SPLITTER: Color = Color {
+        r: 0.6,
+        g: 1.0,
+        b: 0.2,
+        a: 1.0,
+    };
+    pub const ENEMY_ORBITER: Color = Color {
+        r: 0.3,
+        g: 0.5,
+        b: 1.0,
+        a: 1.0,
+    };
+    pub const ENEMY_SHOOTER: Color = WHITE;
+    pub const ENEMY_BULLET: Color = Color {
+        r: 1.0,
+        g: 0.3,
+        b: 0.3,
+        a: 1.0,
+    };
+    pub const BOSS: Color = Color {
+        r: 0.5,
+        g: 0.2,
+        b: 0.6,
+        a: 1.0,
+    };
+    pub const BOSS_WEAK_POINT: Color = YELLOW;
+    pub const BOSS_HEALTH: Color = RED;
     pub const POWERUP: Color = GREEN;
     pub const SHIELD: Color = Color {
         r: 0.0,
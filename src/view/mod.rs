@@ -3,6 +3,6 @@ mod render;
 mod resources;
 mod sound;
 
-pub use self::render::{init_rendering_ctx, render_game};
+pub use self::render::{init_rendering_ctx, render_game, resize_rendering_ctx};
 pub use self::resources::Resources;
 pub use self::sound::play_sounds;
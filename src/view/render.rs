@@ -1,15 +1,20 @@
-use std;
-use ggez::graphics::{self, Color, DrawMode, DrawParam, FillOptions, Mesh, Rect, StrokeOptions, TextFragment};
+use ggez::graphics::{self, Color, DrawMode, DrawParam, FillOptions, Mesh, MeshBuilder, Rect, StrokeOptions, TextFragment};
 use ggez::{conf, Context, ContextBuilder, GameResult};
 use ggez::event::EventLoop;
 use ggez::mint::Point2;
 use geometry::Point;
 
+use geometry::{Advance, Collide, Interpolate, Position, Size};
+use rocket_core::{
+    game_state::Message,
+    high_scores::{HighScores, MAX_NAME_LENGTH},
+    models::{Boss, EnemyKind, Player, PowerupKind, World, BOSS_BODY_RADIUS, BOSS_WEAK_POINT_RADIUS, PLAYER_POLYGON},
+    waves::WaveEvent,
+};
+
 use crate::{
+    input::Action,
     ApplicationState,
-    game_state::Message,
-    geometry::{Advance, Collide, Position, Size},
-    models::{Player, PowerupKind, World, PLAYER_POLYGON},
     view::drawing::color,
     view::Resources,
 };
@@ -17,6 +22,12 @@ use crate::{
 const SPRITE_SIZE: f32 = 32.0;
 const GUN_HEAT_STATUS_WIDTH: f32 = 100.0;
 const GUN_HEAT_STATUS_HEIGHT: f32 = 20.0;
+const POWERUP_STATUS_WIDTH: f32 = 100.0;
+const POWERUP_STATUS_HEIGHT: f32 = 20.0;
+const BOSS_HEALTH_WIDTH: f32 = 400.0;
+const BOSS_HEALTH_HEIGHT: f32 = 12.0;
+// How many times per second an invulnerable player blinks
+const BLINKS_PER_SECOND: f32 = 5.0;
 
 pub fn init_rendering_ctx(game_size: Size) -> GameResult<(Context, EventLoop<()>)> {
     let cb = ContextBuilder::new("rocket", "ggez")
@@ -29,16 +40,32 @@ pub fn init_rendering_ctx(game_size: Size) -> GameResult<(Context, EventLoop<()>
     Ok(ctx)
 }
 
+/// Resizes the window, e.g. when resuming a game that was played in a window of another size
+pub fn resize_rendering_ctx(ctx: &mut Context, game_size: Size) -> GameResult<()> {
+    graphics::set_drawable_size(ctx, game_size.width, game_size.height)?;
+    graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, game_size.width, game_size.height))
+}
+
 /// Renders the game to the screen
 pub fn render_game(app: &mut ApplicationState, ctx: &mut Context) -> GameResult<()> {
     // Clear everything
     graphics::clear(ctx, Color::BLACK);
 
-    // Render the world
-    render_world(ctx, &app.game_state.world, &mut app.resources)?;
+    // Render the world, in between the last update and the next one
+    let alpha = app.timestep.alpha();
+    render_world(ctx, &app.game_state.world, alpha, &mut app.resources)?;
 
-    // Render a message if there is one set
-    render_message(ctx, app)?;
+    // Render the rebinding screen if it's open, otherwise a message if there is one set
+    if app.rebinding.is_some() {
+        render_rebinding_screen(ctx, app)?;
+    } else {
+        render_message(ctx, app)?;
+    }
+
+    if app.paused {
+        let Size { width, height } = app.game_state.world.size;
+        render_lines(ctx, app, &["Paused".to_string()], Point::new(width / 2.0, height / 2.0))?;
+    }
 
     // Render the score
     let fragment = TextFragment::new(format!("Score: {}", app.game_state.score)).font(app.resources.font);
@@ -46,6 +73,35 @@ pub fn render_game(app: &mut ApplicationState, ctx: &mut Context) -> GameResult<
     let pt = point2(Point::new(8.0, 4.0));
     graphics::draw(ctx, &text, DrawParam::new().dest(pt).color(color::SCORE))?;
 
+    // Render the lives and the wave below the score
+    let fragment = TextFragment::new(format!("Lives: {}", app.game_state.lives)).font(app.resources.font);
+    let text = graphics::Text::new(fragment);
+    let pt = point2(Point::new(8.0, 28.0));
+    graphics::draw(ctx, &text, DrawParam::new().dest(pt).color(color::SCORE))?;
+
+    let fragment = TextFragment::new(format!("Wave: {}", app.game_state.waves.wave())).font(app.resources.font);
+    let text = graphics::Text::new(fragment);
+    let pt = point2(Point::new(8.0, 52.0));
+    graphics::draw(ctx, &text, DrawParam::new().dest(pt).color(color::SCORE))?;
+
+    // Announce the next wave while waiting for it, unless a message is shown
+    let waves = &app.game_state.waves;
+    if app.game_state.message.is_none() && waves.is_between_waves() {
+        let mut lines = Vec::new();
+        match waves.last_cleared() {
+            Some(WaveEvent::Cleared { wave, bonus }) => lines.push(format!("Wave {} cleared! +{}", wave, bonus)),
+            Some(WaveEvent::BossDefeated { bonus }) => lines.push(format!("Boss defeated! +{}", bonus)),
+            _ => {}
+        }
+        if waves.boss_is_next() {
+            lines.push("Boss incoming!".to_string());
+        } else {
+            lines.push(format!("Wave {}", waves.wave() + 1));
+        }
+        let Size { width, height } = app.game_state.world.size;
+        render_lines(ctx, app, &lines, Point::new(width / 2.0, height / 3.0))?;
+    }
+
     // Render the gun's heat status in the bottom right of the screen
     let gun = &app.game_state.world.player.gun;
     let color = if !gun.is_available() {
@@ -84,6 +140,12 @@ pub fn render_game(app: &mut ApplicationState, ctx: &mut Context) -> GameResult<
     graphics::draw(ctx, &r1, DrawParam::new())?;
     graphics::draw(ctx, &r2, DrawParam::new())?;
 
+    render_active_powerups(ctx, app)?;
+
+    if let Some(ref boss) = app.game_state.world.boss {
+        render_boss_health(ctx, boss, width)?;
+    }
+
     // NOTE: for limiting FPS rate, see https://github.com/ggez/ggez/issues/171
     // If you want to log the current FPS, uncomment the next line
     // println!("{}", ggez::timer::get_fps(ctx));
@@ -92,6 +154,57 @@ pub fn render_game(app: &mut ApplicationState, ctx: &mut Context) -> GameResult<
     Ok(())
 }
 
+/// Renders a countdown bar for each active powerup in the bottom left of the screen, next to the
+/// powerup's icon
+fn render_active_powerups(ctx: &mut Context, app: &ApplicationState) -> GameResult<()> {
+    let current_time = app.time_controller.current_time();
+    let height = app.game_state.world.size.height;
+    for (i, powerup) in app.game_state.world.player.powerups.iter().enumerate() {
+        let y = height - 40.0 - i as f32 * (POWERUP_STATUS_HEIGHT + 10.0);
+
+        let icon = powerup_image(&app.resources, powerup.kind);
+        let scale = POWERUP_STATUS_HEIGHT / SPRITE_SIZE;
+        let params = DrawParam::new()
+            .dest(point2(Point::new(20.0, y)))
+            .scale(point2(Point::new(scale, scale)))
+            .color(color::POWERUP);
+        graphics::draw(ctx, icon, params)?;
+
+        let x = 30.0 + POWERUP_STATUS_HEIGHT;
+        let rect = Rect {
+            x,
+            y,
+            w: POWERUP_STATUS_WIDTH * powerup.fraction_left(current_time),
+            h: POWERUP_STATUS_HEIGHT,
+        };
+        let fill = Mesh::new_rectangle(ctx, DrawMode::Fill(FillOptions::default()), rect, color::POWERUP)?;
+        let outline = Mesh::new_rectangle(
+            ctx,
+            DrawMode::Stroke(StrokeOptions::default().with_line_width(1.0)),
+            Rect { x, y, w: POWERUP_STATUS_WIDTH, h: POWERUP_STATUS_HEIGHT },
+            color::POWERUP,
+        )?;
+        graphics::draw(ctx, &fill, DrawParam::new())?;
+        graphics::draw(ctx, &outline, DrawParam::new())?;
+    }
+
+    Ok(())
+}
+
+/// Returns the image of the given kind of powerup
+fn powerup_image(resources: &Resources, kind: PowerupKind) -> &graphics::Image {
+    match kind {
+        PowerupKind::Shield => &resources.powerup_shield,
+        PowerupKind::TimeSlow => &resources.powerup_time_slow,
+        PowerupKind::TripleShot => &resources.powerup_triple_shot,
+        PowerupKind::HomingMissiles => &resources.powerup_homing_missiles,
+        PowerupKind::Coolant => &resources.powerup_coolant,
+        PowerupKind::SmartBomb => &resources.powerup_smart_bomb,
+        PowerupKind::Magnet => &resources.powerup_magnet,
+        PowerupKind::ExtraLife => &resources.powerup_extra_life,
+    }
+}
+
 /// Renders the Message struct contained in the game's state to the middle of the screen
 fn render_message(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<()> {
     if let Some(ref message) = app.game_state.message {
@@ -104,9 +217,9 @@ fn render_message(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<(
         let mut draw_text = |text: &str, color: Color, is_title: bool| {
             let fragment = TextFragment::new(text).font(app.resources.font);
             let drawable = graphics::Text::new(fragment);
-            let width = w - (drawable.width(ctx) as f32 / 2.0);
+            let width = w - (drawable.width(ctx) / 2.0);
             let height = if is_title {
-                h - drawable.height(ctx) as f32
+                h - drawable.height(ctx)
             } else {
                 h
             };
@@ -116,30 +229,110 @@ fn render_message(ctx: &mut Context, app: &mut ApplicationState) -> GameResult<(
 
         draw_text(title, color::WHITE, true);
         draw_text(subtitle, color::GREY, false);
+
+        // Below the message, either ask for the player's name or show the high scores
+        let lines = match app.pending_high_score {
+            Some(ref high_score) => vec![
+                "New high score! Type your name and press Enter".to_string(),
+                format!("{}_", high_score.name),
+            ],
+            None => high_score_lines(&app.high_scores),
+        };
+        render_lines(ctx, app, &lines, Point::new(w, h + 50.0))?;
+
+        if app.pending_high_score.is_none() {
+            let lines = ["Press F1 to change the controls".to_string()];
+            render_lines(ctx, app, &lines, Point::new(w, height - 40.0))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders the screen to change the key bindings, listing the keys bound to each action
+fn render_rebinding_screen(ctx: &mut Context, app: &ApplicationState) -> GameResult<()> {
+    let rebinding = match app.rebinding {
+        Some(ref rebinding) => rebinding,
+        None => return Ok(()),
+    };
+
+    let mut lines = vec!["Controls".to_string(), String::new()];
+    for &action in &Action::ALL {
+        let keys: Vec<_> = rebinding.bindings().get(action).iter().map(|b| b.to_string()).collect();
+        let keys = if keys.is_empty() { "(none)".to_string() } else { keys.join(", ") };
+        let marker = if action == rebinding.selected() { ">" } else { " " };
+        lines.push(format!("{} {:<12}  {:<30}", marker, action.name(), keys));
+    }
+
+    lines.push(String::new());
+    if rebinding.is_capturing() {
+        lines.push(format!("Press the key to bind to {} (Esc to cancel)", rebinding.selected().name()));
+    } else {
+        lines.push("Up/Down: select   Enter: add a key   Backspace: clear   Esc: done".to_string());
+    }
+    lines.push(rebinding.status().unwrap_or_default().to_string());
+
+    let Size { width, height } = app.game_state.world.size;
+    render_lines(ctx, app, &lines, Point::new(width / 2.0, height / 2.0 - 100.0))
+}
+
+/// Formats the high score table, one line per entry
+fn high_score_lines(high_scores: &HighScores) -> Vec<String> {
+    if high_scores.entries().is_empty() {
+        return Vec::new();
+    }
+
+    let mut lines = vec!["High scores".to_string()];
+    lines.extend(high_scores.entries().iter().enumerate().map(|(i, entry)| {
+        format!(
+            "{:>2}. {:<width$} {:>6}  {}  {:>4.0}s",
+            i + 1,
+            entry.name,
+            entry.score,
+            entry.date,
+            entry.survival_time,
+            width = MAX_NAME_LENGTH,
+        )
+    }));
+    lines
+}
+
+/// Renders lines of text below each other, horizontally centered around `top.x`
+fn render_lines(ctx: &mut Context, app: &ApplicationState, lines: &[String], top: Point) -> GameResult<()> {
+    let mut y = top.y;
+    for line in lines {
+        let fragment = TextFragment::new(line.as_str()).font(app.resources.font);
+        let drawable = graphics::Text::new(fragment);
+        let point = point2(Point::new(top.x - drawable.width(ctx) / 2.0, y));
+        graphics::draw(ctx, &drawable, DrawParam::new().dest(point).color(color::GREY))?;
+        y += drawable.height(ctx);
     }
 
     Ok(())
 }
 
 /// Renders the world and everything in it
-pub fn render_world(ctx: &mut Context, world: &World, resources: &mut Resources) -> GameResult<()> {
-    render_stars(ctx, world, resources)?;
-    render_particles(ctx, world, resources)?;
-    render_bullets(ctx, world, resources)?;
-    render_enemy(ctx, world, resources)?;
-
-    // Finally draw the player as red
-    if !world.player.is_dead {
-        render_player(ctx, &world.player, resources)?;
+///
+/// Moving objects are drawn between their previous and current position, according to `alpha`
+pub fn render_world(ctx: &mut Context, world: &World, alpha: f32, resources: &mut Resources) -> GameResult<()> {
+    render_stars(ctx, world, alpha, resources)?;
+    render_particles(ctx, world, alpha, resources)?;
+    render_bullets(ctx, world, alpha, resources)?;
+    render_enemy(ctx, world, alpha, resources)?;
+    if let Some(ref boss) = world.boss {
+        render_boss(ctx, boss, boss.interpolated_position(alpha, world.size))?;
+    }
+    render_enemy_bullets(ctx, world, alpha, resources)?;
+
+    // Finally draw the player as red, blinking while it can't be destroyed
+    if !world.player.is_dead && !is_blinked_out(&world.player) {
+        let position = world.player.interpolated_position(alpha, world.size);
+        render_player(ctx, &world.player, position, resources)?;
     }
 
     // Draw powerups
     for powerup in &world.powerups {
-        let image = match powerup.kind {
-            PowerupKind::Shield => &resources.powerup_shield,
-            PowerupKind::TimeSlow => &resources.powerup_time_slow,
-            PowerupKind::TripleShot => &resources.powerup_triple_shot,
-        };
+        let image = powerup_image(resources, powerup.kind);
         let scale = powerup.radius() / SPRITE_SIZE;
         let params = DrawParam::new()
             .dest(point2(powerup.position()))
@@ -152,15 +345,20 @@ pub fn render_world(ctx: &mut Context, world: &World, resources: &mut Resources)
     Ok(())
 }
 
+/// Whether an invulnerable player is in the hidden half of its blink
+fn is_blinked_out(player: &Player) -> bool {
+    player.is_invulnerable() && (player.invulnerability * BLINKS_PER_SECOND * 2.0) as u32 % 2 == 1
+}
+
 /// Renders all the stars in the background
-fn render_stars(ctx: &mut Context, world: &World, resources: &mut Resources) -> GameResult<()> {
+fn render_stars(ctx: &mut Context, world: &World, alpha: f32, resources: &mut Resources) -> GameResult<()> {
     resources.star_sprite.clear();
     // Iterate through the stars list and draw them with a rotation based on their index in the
     // list - this isn't a truly random rotation, but it works visually
     for (i, star) in world.stars.iter().enumerate() {
         let scale = star.size / SPRITE_SIZE;
         resources.star_sprite.add(DrawParam::new()
-            .dest(point2(Point::new(star.x(), star.y())))
+            .dest(point2(star.interpolated_position(alpha, world.size)))
             .rotation((i as f32 / 100.0) * 2.0 * std::f32::consts::PI)
             .scale(point2(Point::new(scale, scale)))
             .color(color::STAR));
@@ -176,13 +374,14 @@ fn render_stars(ctx: &mut Context, world: &World, resources: &mut Resources) ->
 pub fn render_particles(
     ctx: &mut Context,
     world: &World,
+    alpha: f32,
     resources: &mut Resources,
 ) -> GameResult<()> {
     resources.circle_sprite.clear();
     for particle in &world.particles {
         let scale = 0.4 * particle.ttl;
         resources.circle_sprite.add(DrawParam::new()
-            .dest(point2(Point::new(particle.x(), particle.y())))
+            .dest(point2(particle.interpolated_position(alpha, world.size)))
             .offset(point2(Point::new(0.5, 0.5)))
             .scale(point2(Point::new(scale, scale)))
             .color(color::PARTICLE));
@@ -198,16 +397,17 @@ pub fn render_particles(
 pub fn render_bullets(
     ctx: &mut Context,
     world: &World,
+    alpha: f32,
     resources: &mut Resources,
 ) -> GameResult<()> {
     resources.circle_sprite.clear();
     for bullet in &world.bullets {
         let scale = bullet.radius() / SPRITE_SIZE;
         resources.circle_sprite.add(DrawParam::new()
-            .dest(point2(bullet.position()))
+            .dest(point2(bullet.interpolated_position(alpha, world.size)))
             .offset(point2(Point::new(0.5, 0.5)))
             .scale(point2(Point::new(scale, scale)))
-            .color(color::BULLET)
+            .color(if bullet.homing { color::HOMING_BULLET } else { color::BULLET })
         );
     }
     graphics::draw(
@@ -217,42 +417,142 @@ pub fn render_bullets(
     )
 }
 
-/// Renders an enemy
-pub fn render_enemy(ctx: &mut Context, world: &World, resources: &mut Resources) -> GameResult<()> {
+/// Renders the bullets shot by enemies
+pub fn render_enemy_bullets(
+    ctx: &mut Context,
+    world: &World,
+    alpha: f32,
+    resources: &mut Resources,
+) -> GameResult<()> {
     resources.circle_sprite.clear();
-    for enemy in &world.enemies {
-        let scale = enemy.radius() * 2.0 / SPRITE_SIZE;
+    for bullet in &world.enemy_bullets {
+        let scale = bullet.radius() / SPRITE_SIZE;
         resources.circle_sprite.add(DrawParam::new()
-            .dest(point2(enemy.position()))
+            .dest(point2(bullet.interpolated_position(alpha, world.size)))
             .offset(point2(Point::new(0.5, 0.5)))
             .scale(point2(Point::new(scale, scale)))
-            .color(color::ENEMY));
+            .color(color::ENEMY_BULLET)
+        );
     }
     graphics::draw(
         ctx,
         &resources.circle_sprite,
-        DrawParam {
-            ..Default::default()
-        },
+        DrawParam::new(),
     )
 }
 
-/// Renders the player
-pub fn render_player(ctx: &mut Context, player: &Player, resources: &Resources) -> GameResult<()> {
-    // Render shield if one is active
-    let pt = Point::new(player.x(), player.y());
-    if let Some(powerup) = player.powerup {
-        if powerup == PowerupKind::Shield {
-            let scale = (player.radius() + 30.0) / SPRITE_SIZE;
-            let params = DrawParam::new()
-                .dest(point2(pt))
-                .offset(point2(Point::new(0.5, 0.5)))
-                .scale(point2(Point::new(scale, scale)))
-                .color(color::SHIELD);
-            graphics::draw(ctx, &resources.circle_image, params)?;
+/// Renders the enemies, each kind with its own shape and color
+///
+/// Round enemies are drawn as sprites, the others are built into a single mesh
+pub fn render_enemy(ctx: &mut Context, world: &World, alpha: f32, resources: &mut Resources) -> GameResult<()> {
+    resources.circle_sprite.clear();
+    let mut shapes = MeshBuilder::new();
+    let mut has_shapes = false;
+    for enemy in &world.enemies {
+        let position = enemy.interpolated_position(alpha, world.size);
+        let radius = enemy.radius();
+        // Turns a point of the enemy's outline (facing right) into a point of the world
+        let outline = |x: f32, y: f32| point2(Point::new(x, y).rotate(enemy.direction()).translate(&position));
+
+        match enemy.kind {
+            EnemyKind::Chaser | EnemyKind::Fragment | EnemyKind::Splitter => {
+                let color = if enemy.kind == EnemyKind::Splitter { color::ENEMY_SPLITTER } else { color::ENEMY };
+                let scale = radius * 2.0 / SPRITE_SIZE;
+                resources.circle_sprite.add(DrawParam::new()
+                    .dest(point2(position))
+                    .offset(point2(Point::new(0.5, 0.5)))
+                    .scale(point2(Point::new(scale, scale)))
+                    .color(color));
+            }
+            EnemyKind::Tank => {
+                // A square that fades as it loses hit points
+                let health = enemy.hit_points as f32 / enemy.kind.hit_points() as f32;
+                let color = Color { a: 0.4 + 0.6 * health, ..color::ENEMY_TANK };
+                let corners = [
+                    outline(-radius, -radius),
+                    outline(radius, -radius),
+                    outline(radius, radius),
+                    outline(-radius, radius),
+                ];
+                shapes.polygon(DrawMode::fill(), &corners, color)?;
+                has_shapes = true;
+            }
+            EnemyKind::Dasher => {
+                // A diamond pointing where the dasher is heading
+                let corners = [outline(1.6 * radius, 0.0), outline(0.0, radius), outline(-radius, 0.0), outline(0.0, -radius)];
+                shapes.polygon(DrawMode::fill(), &corners, color::ENEMY_DASHER)?;
+                has_shapes = true;
+            }
+            EnemyKind::Orbiter => {
+                // A ring around a small core
+                shapes.circle(DrawMode::stroke(2.0), point2(position), radius, 0.5, color::ENEMY_ORBITER)?;
+                shapes.circle(DrawMode::fill(), point2(position), radius / 3.0, 0.5, color::ENEMY_ORBITER)?;
+                has_shapes = true;
+            }
+            EnemyKind::Shooter => {
+                // An arrowhead aimed at the player
+                let corners = [
+                    outline(radius, 0.0),
+                    outline(-radius, radius),
+                    outline(-radius / 2.0, 0.0),
+                    outline(-radius, -radius),
+                ];
+                shapes.polygon(DrawMode::fill(), &corners, color::ENEMY_SHOOTER)?;
+                has_shapes = true;
+            }
         }
     }
 
+    graphics::draw(ctx, &resources.circle_sprite, DrawParam::default())?;
+    // Building an empty mesh fails, so only draw the shapes if there are any
+    if has_shapes {
+        let mesh = shapes.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::default())?;
+    }
+    Ok(())
+}
+
+/// Renders the boss at the given position: its body, with the weak points turning around it
+pub fn render_boss(ctx: &mut Context, boss: &Boss, position: Point) -> GameResult<()> {
+    let mut shapes = MeshBuilder::new();
+    shapes.circle(DrawMode::fill(), point2(position), BOSS_BODY_RADIUS, 0.5, color::BOSS)?;
+    shapes.circle(DrawMode::stroke(3.0), point2(position), BOSS_BODY_RADIUS, 0.5, color::BOSS_WEAK_POINT)?;
+    for point in &boss.weak_points() {
+        // The weak points are computed from the current position, move them along
+        let point = *point - boss.position() + position;
+        shapes.circle(DrawMode::fill(), point2(point), BOSS_WEAK_POINT_RADIUS, 0.5, color::BOSS_WEAK_POINT)?;
+    }
+    let mesh = shapes.build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::default())
+}
+
+/// Renders the health of the boss as a bar at the top center of the screen
+fn render_boss_health(ctx: &mut Context, boss: &Boss, screen_width: f32) -> GameResult<()> {
+    let x = (screen_width - BOSS_HEALTH_WIDTH) / 2.0;
+    let y = 12.0;
+    let health = boss.health as f32 / boss.max_health as f32;
+    let fill = Rect { x, y, w: BOSS_HEALTH_WIDTH * health, h: BOSS_HEALTH_HEIGHT };
+    let outline = Rect { x, y, w: BOSS_HEALTH_WIDTH, h: BOSS_HEALTH_HEIGHT };
+
+    let r1 = Mesh::new_rectangle(ctx, DrawMode::fill(), fill, color::BOSS_HEALTH)?;
+    let r2 = Mesh::new_rectangle(ctx, DrawMode::stroke(1.0), outline, color::BOSS_HEALTH)?;
+    graphics::draw(ctx, &r1, DrawParam::new())?;
+    graphics::draw(ctx, &r2, DrawParam::new())
+}
+
+/// Renders the player at the given position
+pub fn render_player(ctx: &mut Context, player: &Player, pt: Point, resources: &Resources) -> GameResult<()> {
+    // Render shield if one is active
+    if player.has_powerup(PowerupKind::Shield) {
+        let scale = (player.radius() + 23.0) / SPRITE_SIZE;
+        let params = DrawParam::new()
+            .dest(point2(pt))
+            .offset(point2(Point::new(0.5, 0.5)))
+            .scale(point2(Point::new(scale, scale)))
+            .color(color::SHIELD);
+        graphics::draw(ctx, &resources.circle_image, params)?;
+    }
+
     // Render the player
     let p1 = point2(Point::new(PLAYER_POLYGON[0][0], PLAYER_POLYGON[0][1]));
     let p2 = point2(Point::new(PLAYER_POLYGON[1][0], PLAYER_POLYGON[1][1]));
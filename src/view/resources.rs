@@ -17,17 +17,24 @@ pub struct Resources {
     pub(in crate::view) powerup_shield: Image,
     pub(in crate::view) powerup_time_slow: Image,
     pub(in crate::view) powerup_triple_shot: Image,
+    pub(in crate::view) powerup_homing_missiles: Image,
+    pub(in crate::view) powerup_coolant: Image,
+    pub(in crate::view) powerup_smart_bomb: Image,
+    pub(in crate::view) powerup_magnet: Image,
+    pub(in crate::view) powerup_extra_life: Image,
     pub(in crate::view) circle_image: Image,
     pub(in crate::view) star_sprite: SpriteBatch,
     pub(in crate::view) circle_sprite: SpriteBatch,
 
     // Sounds
     pub(in crate::view) shot_sound: audio::Source,
+    pub(in crate::view) enemy_shot_sound: audio::Source,
     pub(in crate::view) powerup_sound: audio::Source,
     pub(in crate::view) game_start_sound: audio::Source,
     pub(in crate::view) enemy_spawn_sound: audio::Source,
     pub(in crate::view) enemy_destroyed_sound: audio::Source,
     pub(in crate::view) player_destroyed_sound: audio::Source,
+    pub(in crate::view) boss_destroyed_sound: audio::Source,
 }
 
 impl Resources {
@@ -39,6 +46,13 @@ impl Resources {
             sound
         };
 
+        // Enemies shoot with a deeper version of the player's gun
+        let mut enemy_shot_sound = new_with_volume(ctx, "/audio/shot.ogg", 0.3);
+        enemy_shot_sound.set_pitch(0.6);
+        // And bosses explode like a much bigger player
+        let mut boss_destroyed_sound = new_with_volume(ctx, "/audio/player_destroyed.ogg", 1.0);
+        boss_destroyed_sound.set_pitch(0.5);
+
         let circle_image = Image::new(ctx, "/images/circle.png").unwrap();
         Resources {
             font: Font::new(ctx, "/FiraMono-Bold.ttf").unwrap(),
@@ -46,17 +60,24 @@ impl Resources {
             powerup_shield: Image::new(ctx, "/images/powerup_shield.png").unwrap(),
             powerup_time_slow: Image::new(ctx, "/images/powerup_time_slow.png").unwrap(),
             powerup_triple_shot: Image::new(ctx, "/images/powerup_triple_shot.png").unwrap(),
+            powerup_homing_missiles: Image::new(ctx, "/images/powerup_homing_missiles.png").unwrap(),
+            powerup_coolant: Image::new(ctx, "/images/powerup_coolant.png").unwrap(),
+            powerup_smart_bomb: Image::new(ctx, "/images/powerup_smart_bomb.png").unwrap(),
+            powerup_magnet: Image::new(ctx, "/images/powerup_magnet.png").unwrap(),
+            powerup_extra_life: Image::new(ctx, "/images/powerup_extra_life.png").unwrap(),
 
             circle_image: circle_image.clone(),
             star_sprite: SpriteBatch::new(Image::new(ctx, "/images/star.png").unwrap()),
             circle_sprite: SpriteBatch::new(circle_image),
 
             shot_sound: new_with_volume(ctx, "/audio/shot.ogg", 0.2),
+            enemy_shot_sound,
             powerup_sound: new_with_volume(ctx, "/audio/powerup.ogg", 1.0),
             game_start_sound: new_with_volume(ctx, "/audio/game_start.ogg", 1.0),
             enemy_spawn_sound: new_with_volume(ctx, "/audio/enemy_spawn.ogg", 0.4),
             enemy_destroyed_sound: new_with_volume(ctx, "/audio/enemy_destroyed.ogg", 1.0),
             player_destroyed_sound: new_with_volume(ctx, "/audio/player_destroyed.ogg", 1.0),
+            boss_destroyed_sound,
         }
     }
 }
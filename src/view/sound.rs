@@ -1,10 +1,9 @@
 use ggez::audio::SoundSource;
 use ggez::{Context, GameResult};
 
-use crate::{
-    controllers::Event,
-    view::Resources,
-};
+use rocket_core::Event;
+
+use crate::view::Resources;
 
 pub fn play_sounds(ctx: &Context, events: &mut Vec<Event>, resources: &mut Resources) -> GameResult<()> {
     use self::Event::*;
@@ -12,9 +11,15 @@ pub fn play_sounds(ctx: &Context, events: &mut Vec<Event>, resources: &mut Resou
         match event {
             EnemyDestroyed => resources.enemy_destroyed_sound.play(ctx)?,
             PlayerDestroyed => resources.player_destroyed_sound.play(ctx)?,
+            LifeGained => resources.powerup_sound.play(ctx)?,
             PowerupGained => resources.powerup_sound.play(ctx)?,
             ShotFired => resources.shot_sound.play(ctx)?,
+            EnemyShotFired => resources.enemy_shot_sound.play(ctx)?,
             EnemySpawned => resources.enemy_spawn_sound.play(ctx)?,
+            WaveStarted => resources.game_start_sound.play(ctx)?,
+            WaveCleared => resources.powerup_sound.play(ctx)?,
+            BossArrived => resources.game_start_sound.play(ctx)?,
+            BossDestroyed => resources.boss_destroyed_sound.play(ctx)?,
             GameStart => resources.game_start_sound.play(ctx)?
         }
     }